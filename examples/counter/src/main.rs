@@ -1,7 +1,11 @@
 use iced_xml_core::IcedComponent;
 use iced_xml_derive::IcedComponent;
 fn main() -> iced::Result {
-    iced::run("Counter", Counter::update, Counter::view)
+    #[cfg(feature = "hot-reload")]
+    iced_xml_runtime::hot_reload::install();
+    iced::application("Counter", Counter::update, Counter::view)
+        .subscription(Counter::subscription)
+        .run()
 }
 
 #[derive(Default, IcedComponent)]
@@ -14,6 +18,9 @@ struct Counter {
 enum Message {
     Increment,
     Decrement,
+    /// Sent periodically, so template changes show up while the app is idle
+    #[cfg(feature = "hot-reload")]
+    Reload,
 }
 
 impl Counter {
@@ -25,8 +32,17 @@ impl Counter {
             Message::Decrement => {
                 self.value -= 1;
             }
+            #[cfg(feature = "hot-reload")]
+            Message::Reload => {}
         }
     }
+
+    fn subscription(&self) -> iced::Subscription<Message> {
+        #[cfg(feature = "hot-reload")]
+        return iced::time::every(std::time::Duration::from_millis(500)).map(|_| Message::Reload);
+        #[cfg(not(feature = "hot-reload"))]
+        iced::Subscription::none()
+    }
 }
 
 // // TODO: This should be generated by a derive macro!
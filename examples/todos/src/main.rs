@@ -1,357 +1,347 @@
-// use iced::{
-//     Task,
-//     widget::{self, text_input},
-//     window,
-// };
-
-fn main() {
-    println!("Hello, world!");
+use iced::{
+    Center, Element, Fill, Font, Subscription, keyboard,
+    widget::{self, Text, column, container, scrollable, text, text_input},
+};
+use iced_xml_core::IcedComponent;
+use iced_xml_derive::IcedComponent;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+fn main() -> iced::Result {
+    iced::application(Todos::title, Todos::update, Todos::view_with_tasks)
+        .subscription(Todos::subscription)
+        .font(Todos::ICON_FONT)
+        .window_size((500.0, 800.0))
+        .run_with(Todos::new)
 }
 
-// #[derive(Debug)]
-// enum Todos {
-//     Loading,
-//     Loaded(State),
-// }
-
-// impl Todos {
-//     const ICON_FONT: &'static [u8] = include_bytes!("../fonts/icons.ttf");
-
-//     fn new() -> (Self, Task<Message>) {
-//         (
-//             Self::Loading,
-//             Task::perform(SavedState::load(), Message::Loaded),
-//         )
-//     }
-
-//     fn title(&self) -> String {
-//         let dirty = match self {
-//             Todos::Loading => false,
-//             Todos::Loaded(state) => state.dirty,
-//         };
-
-//         format!("Todos{} - Iced", if dirty { "*" } else { "" })
-//     }
-
-//     fn update(&mut self, message: Message) -> Task<Message> {
-//         match self {
-//             Todos::Loading => {
-//                 match message {
-//                     Message::Loaded(Ok(state)) => {
-//                         *self = Todos::Loaded(State {
-//                             input_value: state.input_value,
-//                             filter: state.filter,
-//                             tasks: state.tasks,
-//                             ..State::default()
-//                         });
-//                     }
-//                     Message::Loaded(Err(_)) => {
-//                         *self = Todos::Loaded(State::default());
-//                     }
-//                     _ => {}
-//                 }
-
-//                 text_input::focus("new-task")
-//             }
-//             Todos::Loaded(state) => {
-//                 let mut saved = false;
-
-//                 let command = match message {
-//                     Message::InputChanged(value) => {
-//                         state.input_value = value;
-
-//                         Task::none()
-//                     }
-//                     Message::CreateTask => {
-//                         if !state.input_value.is_empty() {
-//                             state.tasks.push(Task::done(state.input_value.clone()));
-//                             state.input_value.clear();
-//                         }
-
-//                         Task::none()
-//                     }
-//                     Message::FilterChanged(filter) => {
-//                         state.filter = filter;
-
-//                         Task::none()
-//                     }
-//                     Message::TaskMessage(i, TaskMessage::Delete) => {
-//                         state.tasks.remove(i);
-
-//                         Task::none()
-//                     }
-//                     Message::TaskMessage(i, task_message) => {
-//                         if let Some(task) = state.tasks.get_mut(i) {
-//                             let should_focus = matches!(task_message, TaskMessage::Edit);
-
-//                             task.update(task_message);
-
-//                             if should_focus {
-//                                 let id = Task::text_input_id(i);
-//                                 Task::batch(vec![
-//                                     text_input::focus(id.clone()),
-//                                     text_input::select_all(id),
-//                                 ])
-//                             } else {
-//                                 Task::none()
-//                             }
-//                         } else {
-//                             Task::none()
-//                         }
-//                     }
-//                     Message::Saved(_result) => {
-//                         state.saving = false;
-//                         saved = true;
-
-//                         Task::none()
-//                     }
-//                     Message::TabPressed { shift } => {
-//                         if shift {
-//                             widget::focus_previous()
-//                         } else {
-//                             widget::focus_next()
-//                         }
-//                     }
-//                     Message::ToggleFullscreen(mode) => {
-//                         window::get_latest().and_then(move |window| window::set_mode(window, mode))
-//                     }
-//                     Message::Loaded(_) => Task::none(),
-//                 };
-
-//                 if !saved {
-//                     state.dirty = true;
-//                 }
-
-//                 let save = if state.dirty && !state.saving {
-//                     state.dirty = false;
-//                     state.saving = true;
-
-//                     Task::perform(
-//                         SavedState {
-//                             input_value: state.input_value.clone(),
-//                             filter: state.filter,
-//                             tasks: state.tasks.clone(),
-//                         }
-//                         .save(),
-//                         Message::Saved,
-//                     )
-//                 } else {
-//                     Task::none()
-//                 };
-
-//                 Task::batch(vec![command, save])
-//             }
-//         }
-//     }
-
-//     fn subscription(&self) -> Subscription<Message> {
-//         use keyboard::key;
-
-//         keyboard::on_key_press(|key, modifiers| {
-//             let keyboard::Key::Named(key) = key else {
-//                 return None;
-//             };
-
-//             match (key, modifiers) {
-//                 (key::Named::Tab, _) => Some(Message::TabPressed {
-//                     shift: modifiers.shift(),
-//                 }),
-//                 (key::Named::ArrowUp, keyboard::Modifiers::SHIFT) => {
-//                     Some(Message::ToggleFullscreen(window::Mode::Fullscreen))
-//                 }
-//                 (key::Named::ArrowDown, keyboard::Modifiers::SHIFT) => {
-//                     Some(Message::ToggleFullscreen(window::Mode::Windowed))
-//                 }
-//                 _ => None,
-//             }
-//         })
-//     }
-// }
-
-// #[derive(Debug, Default)]
-// struct State {
-//     input_value: String,
-//     filter: Filter,
-//     tasks: Vec<Task>,
-//     dirty: bool,
-//     saving: bool,
-// }
-
-// #[derive(Debug, Clone)]
-// enum Message {
-//     Loaded(Result<SavedState, LoadError>),
-//     Saved(Result<(), SaveError>),
-//     InputChanged(String),
-//     CreateTask,
-//     FilterChanged(Filter),
-//     TaskMessage(usize, TaskMessage),
-//     TabPressed { shift: bool },
-//     ToggleFullscreen(window::Mode),
-// }
-
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// struct Task {
-//     #[serde(default = "Uuid::new_v4")]
-//     id: Uuid,
-//     description: String,
-//     completed: bool,
-
-//     #[serde(skip)]
-//     state: TaskState,
-// }
-
-// #[derive(Debug, Clone)]
-// pub enum TaskState {
-//     Idle,
-//     Editing,
-// }
-
-// impl Default for TaskState {
-//     fn default() -> Self {
-//         Self::Idle
-//     }
-// }
-
-// #[derive(Debug, Clone)]
-// pub enum TaskMessage {
-//     Completed(bool),
-//     Edit,
-//     DescriptionEdited(String),
-//     FinishEdition,
-//     Delete,
-// }
-
-// impl Task {
-//     fn text_input_id(i: usize) -> text_input::Id {
-//         text_input::Id::new(format!("task-{i}"))
-//     }
-
-//     fn new(description: String) -> Self {
-//         Task {
-//             id: Uuid::new_v4(),
-//             description,
-//             completed: false,
-//             state: TaskState::Idle,
-//         }
-//     }
-
-//     fn update(&mut self, message: TaskMessage) {
-//         match message {
-//             TaskMessage::Completed(completed) => {
-//                 self.completed = completed;
-//             }
-//             TaskMessage::Edit => {
-//                 self.state = TaskState::Editing;
-//             }
-//             TaskMessage::DescriptionEdited(new_description) => {
-//                 self.description = new_description;
-//             }
-//             TaskMessage::FinishEdition => {
-//                 if !self.description.is_empty() {
-//                     self.state = TaskState::Idle;
-//                 }
-//             }
-//             TaskMessage::Delete => {}
-//         }
-//     }
-// }
-
-// #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
-// pub enum Filter {
-//     #[default]
-//     All,
-//     Active,
-//     Completed,
-// }
-
-// impl Filter {
-//     fn matches(self, task: &Task) -> bool {
-//         match self {
-//             Filter::All => true,
-//             Filter::Active => !task.completed,
-//             Filter::Completed => task.completed,
-//         }
-//     }
-// }
-
-// fn icon(unicode: char) -> Text<'static> {
-//     text(unicode.to_string())
-//         .font(Font::with_name("Iced-Todos-Icons"))
-//         .width(20)
-//         .align_x(Center)
-//         .shaping(text::Shaping::Basic)
-// }
-
-// fn edit_icon() -> Text<'static> {
-//     icon('\u{F303}')
-// }
-
-// fn delete_icon() -> Text<'static> {
-//     icon('\u{F1F8}')
-// }
-
-// // Persistence
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// struct SavedState {
-//     input_value: String,
-//     filter: Filter,
-//     tasks: Vec<Task>,
-// }
-
-// #[derive(Debug, Clone)]
-// enum LoadError {
-//     File,
-//     Format,
-// }
-
-// #[derive(Debug, Clone)]
-// enum SaveError {
-//     Write,
-//     Format,
-// }
-
-// impl SavedState {
-//     fn path() -> std::path::PathBuf {
-//         let mut path =
-//             if let Some(project_dirs) = directories::ProjectDirs::from("rs", "Iced", "Todos") {
-//                 project_dirs.data_dir().into()
-//             } else {
-//                 std::env::current_dir().unwrap_or_default()
-//             };
-
-//         path.push("todos.json");
-
-//         path
-//     }
-
-//     async fn load() -> Result<SavedState, LoadError> {
-//         let contents = tokio::fs::read_to_string(Self::path())
-//             .await
-//             .map_err(|_| LoadError::File)?;
-
-//         serde_json::from_str(&contents).map_err(|_| LoadError::Format)
-//     }
-
-//     async fn save(self) -> Result<(), SaveError> {
-//         let json = serde_json::to_string_pretty(&self).map_err(|_| SaveError::Format)?;
-
-//         let path = Self::path();
-
-//         if let Some(dir) = path.parent() {
-//             tokio::fs::create_dir_all(dir)
-//                 .await
-//                 .map_err(|_| SaveError::Write)?;
-//         }
-
-//         {
-//             tokio::fs::write(path, json.as_bytes())
-//                 .await
-//                 .map_err(|_| SaveError::Write)?;
-//         }
-
-//         // This is a simple way to save at most once every couple seconds
-//         tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-
-//         Ok(())
-//     }
-// }
+#[derive(Debug, IcedComponent)]
+#[source = "Todos.ixml"]
+enum Todos {
+    Loading,
+    Loaded(State),
+}
+
+impl Todos {
+    const ICON_FONT: &'static [u8] = include_bytes!("../fonts/icons.ttf");
+
+    fn new() -> (Self, iced::Task<Message>) {
+        (
+            Self::Loading,
+            iced::Task::perform(SavedState::load(), Message::Loaded),
+        )
+    }
+
+    fn title(&self) -> String {
+        let dirty = match self {
+            Todos::Loading => false,
+            Todos::Loaded(state) => state.dirty,
+        };
+
+        format!("Todos{} - Iced", if dirty { "*" } else { "" })
+    }
+
+    fn update(&mut self, message: Message) -> iced::Task<Message> {
+        match self {
+            Todos::Loading => {
+                match message {
+                    Message::Loaded(Ok(state)) => {
+                        *self = Todos::Loaded(State {
+                            input_value: state.input_value,
+                            filter: state.filter,
+                            tasks: state.tasks,
+                            ..State::default()
+                        });
+                    }
+                    Message::Loaded(Err(_)) => {
+                        *self = Todos::Loaded(State::default());
+                    }
+                    _ => {}
+                }
+
+                text_input::focus(text_input::Id::new("new-task"))
+            }
+            Todos::Loaded(state) => {
+                let mut saved = false;
+
+                let command = match message {
+                    Message::InputChanged(value) => {
+                        state.input_value = value;
+
+                        iced::Task::none()
+                    }
+                    Message::CreateTask => {
+                        if !state.input_value.is_empty() {
+                            state.tasks.push(Task::new(state.input_value.clone()));
+                            state.input_value.clear();
+                        }
+
+                        iced::Task::none()
+                    }
+                    Message::FilterChanged(filter) => {
+                        state.filter = filter;
+
+                        iced::Task::none()
+                    }
+                    Message::TaskChanged(i, TaskMessage::Delete) => {
+                        state.tasks.remove(i);
+
+                        iced::Task::none()
+                    }
+                    Message::TaskChanged(i, task_message) => {
+                        if let Some(task) = state.tasks.get_mut(i) {
+                            task.update(task_message);
+                        }
+
+                        iced::Task::none()
+                    }
+                    Message::Saved(_result) => {
+                        state.saving = false;
+                        saved = true;
+
+                        iced::Task::none()
+                    }
+                    Message::TabPressed { shift } => {
+                        if shift {
+                            widget::focus_previous()
+                        } else {
+                            widget::focus_next()
+                        }
+                    }
+                    Message::Loaded(_) => iced::Task::none(),
+                };
+
+                if !saved {
+                    state.dirty = true;
+                }
+
+                let save = if state.dirty && !state.saving {
+                    state.dirty = false;
+                    state.saving = true;
+
+                    iced::Task::perform(
+                        SavedState {
+                            input_value: state.input_value.clone(),
+                            filter: state.filter,
+                            tasks: state.tasks.clone(),
+                        }
+                        .save(),
+                        Message::Saved,
+                    )
+                } else {
+                    iced::Task::none()
+                };
+
+                iced::Task::batch(vec![command, save])
+            }
+        }
+    }
+
+    /// The header and filters come from the template, which has no elements for text inputs
+    /// or lists yet, so those are added here.
+    fn view_with_tasks(&self) -> Element<'_, Message> {
+        let header = IcedComponent::view(self);
+        let Todos::Loaded(state) = self else {
+            return container(header).center(Fill).into();
+        };
+
+        let input = text_input("What needs to be done?", &state.input_value)
+            .id(text_input::Id::new("new-task"))
+            .on_input(Message::InputChanged)
+            .on_submit(Message::CreateTask)
+            .padding(15)
+            .size(30);
+
+        let tasks = column(
+            state
+                .tasks
+                .iter()
+                .enumerate()
+                .filter(|(_, task)| state.filter.matches(task))
+                .map(|(i, task)| {
+                    task.view()
+                        .map(move |message| Message::TaskChanged(i, message))
+                }),
+        )
+        .spacing(10);
+
+        scrollable(
+            container(column![header, input, tasks].spacing(20).max_width(800))
+                .center_x(Fill)
+                .padding(40),
+        )
+        .into()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        keyboard::on_key_press(|key, modifiers| {
+            let keyboard::Key::Named(keyboard::key::Named::Tab) = key else {
+                return None;
+            };
+
+            Some(Message::TabPressed {
+                shift: modifiers.shift(),
+            })
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    input_value: String,
+    filter: Filter,
+    tasks: Vec<Task>,
+    dirty: bool,
+    saving: bool,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Loaded(Result<SavedState, LoadError>),
+    Saved(Result<(), SaveError>),
+    InputChanged(String),
+    CreateTask,
+    FilterChanged(Filter),
+    TaskChanged(usize, TaskMessage),
+    TabPressed { shift: bool },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IcedComponent)]
+#[source = "Task.ixml"]
+struct Task {
+    #[serde(default = "Uuid::new_v4")]
+    id: Uuid,
+    description: String,
+    completed: bool,
+
+    #[serde(skip)]
+    state: TaskState,
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum TaskState {
+    #[default]
+    Idle,
+    Editing,
+}
+
+#[derive(Debug, Clone)]
+pub enum TaskMessage {
+    Completed(bool),
+    Edit,
+    FinishEdition,
+    Delete,
+}
+
+impl Task {
+    fn new(description: String) -> Self {
+        Task {
+            id: Uuid::new_v4(),
+            description,
+            completed: false,
+            state: TaskState::Idle,
+        }
+    }
+
+    fn update(&mut self, message: TaskMessage) {
+        match message {
+            TaskMessage::Completed(completed) => {
+                self.completed = completed;
+            }
+            TaskMessage::Edit => {
+                self.state = TaskState::Editing;
+            }
+            TaskMessage::FinishEdition => {
+                self.state = TaskState::Idle;
+            }
+            TaskMessage::Delete => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Filter {
+    #[default]
+    All,
+    Active,
+    Completed,
+}
+
+impl Filter {
+    fn matches(self, task: &Task) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Active => !task.completed,
+            Filter::Completed => task.completed,
+        }
+    }
+}
+
+fn tasks_left(tasks: &[Task]) -> String {
+    let tasks_left = tasks.iter().filter(|task| !task.completed).count();
+
+    format!(
+        "{tasks_left} {} left",
+        if tasks_left == 1 { "task" } else { "tasks" }
+    )
+}
+
+fn icon(unicode: char) -> Text<'static> {
+    text(unicode.to_string())
+        .font(Font::with_name("Iced-Todos-Icons"))
+        .width(20)
+        .align_x(Center)
+        .shaping(text::Shaping::Basic)
+}
+
+fn edit_icon() -> Text<'static> {
+    icon('\u{F303}')
+}
+
+fn delete_icon() -> Text<'static> {
+    icon('\u{F1F8}')
+}
+
+fn check_icon(completed: bool) -> Text<'static> {
+    text(if completed { "[x]" } else { "[ ]" })
+}
+
+// Persistence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedState {
+    input_value: String,
+    filter: Filter,
+    tasks: Vec<Task>,
+}
+
+#[derive(Debug, Clone)]
+enum LoadError {
+    File,
+    Format,
+}
+
+#[derive(Debug, Clone)]
+enum SaveError {
+    Write,
+    Format,
+}
+
+impl SavedState {
+    fn path() -> std::path::PathBuf {
+        std::env::temp_dir().join("iced-xml-todos.json")
+    }
+
+    async fn load() -> Result<SavedState, LoadError> {
+        let contents = std::fs::read_to_string(Self::path()).map_err(|_| LoadError::File)?;
+
+        serde_json::from_str(&contents).map_err(|_| LoadError::Format)
+    }
+
+    async fn save(self) -> Result<(), SaveError> {
+        let json = serde_json::to_string_pretty(&self).map_err(|_| SaveError::Format)?;
+
+        std::fs::write(Self::path(), json.as_bytes()).map_err(|_| SaveError::Write)?;
+
+        Ok(())
+    }
+}
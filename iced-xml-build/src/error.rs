@@ -0,0 +1,36 @@
+use std::{fmt::Display, path::PathBuf};
+
+#[derive(Debug)]
+pub enum Error {
+    /// `OUT_DIR` is not set, as outside of a build script.
+    MissingOutDir,
+    /// A template or the generated code could not be read or written.
+    Io {
+        path: PathBuf,
+        error: std::io::Error,
+    },
+    /// A template is invalid, the message points at the offending line.
+    Template { path: PathBuf, message: String },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::MissingOutDir => write!(
+                f,
+                "OUT_DIR is not set, templates are compiled from a build script"
+            ),
+            Error::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            Error::Template { message, .. } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { error, .. } => Some(error),
+            Error::MissingOutDir | Error::Template { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,314 @@
+//! Compiles templates into rust code from a build script, for crates which would rather not
+//! use `#[derive(IcedComponent)]`.
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     if let Err(e) = iced_xml_build::compile_dir("src/ui") {
+//!         panic!("{e}");
+//!     }
+//! }
+//!
+//! // next to `struct Counter`, for `src/ui/counter.ixml`
+//! include!(concat!(env!("OUT_DIR"), "/ixml/counter.rs"));
+//! ```
+//!
+//! Every template has to name the type it is rendered for with `Model`.
+mod error;
+
+use std::path::{Path, PathBuf};
+
+use iced_xml_codegen::{Template, TemplateError};
+use iced_xml_core::Component;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+pub use error::Error;
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Compiles every template below `dir` into `$OUT_DIR/ixml`, keeping the directory structure,
+/// and tells cargo to rerun the build script when one of them or a template they include
+/// changes.
+pub fn compile_dir(dir: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").ok_or(Error::MissingOutDir)?);
+
+    // Picks up templates which are added later on
+    println!("cargo:rerun-if-changed={}", dir.display());
+    let mut included = Vec::new();
+    let templates = compile_dir_including(dir, &out_dir.join("ixml"), &mut included)?;
+    for template in templates.iter().chain(&included) {
+        println!("cargo:rerun-if-changed={}", template.display());
+    }
+
+    Ok(templates)
+}
+
+/// Compiles every template below `dir` into `out_dir`, returning the compiled templates.
+pub fn compile_dir_to(dir: impl AsRef<Path>, out_dir: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    compile_dir_including(dir.as_ref(), out_dir.as_ref(), &mut Vec::new())
+}
+
+/// Compiles every template below `dir`, adding the templates they include or import to
+/// `included` unless they are compiled themselves.
+fn compile_dir_including(
+    dir: &Path,
+    out_dir: &Path,
+    included: &mut Vec<PathBuf>,
+) -> Result<Vec<PathBuf>> {
+    let mut templates = Vec::new();
+    find_templates(dir, &mut templates)?;
+    templates.sort();
+
+    for template in &templates {
+        let (source, files) = compile(template)?;
+        for file in files {
+            if !templates.contains(&file) && !included.contains(&file) {
+                included.push(file);
+            }
+        }
+        let output = out_dir
+            .join(template.strip_prefix(dir).unwrap_or(template))
+            .with_extension("rs");
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent).map_err(|error| Error::Io {
+                path: parent.to_path_buf(),
+                error,
+            })?;
+        }
+        std::fs::write(&output, source).map_err(|error| Error::Io {
+            path: output.clone(),
+            error,
+        })?;
+    }
+
+    Ok(templates)
+}
+
+fn find_templates(dir: &Path, templates: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = std::fs::read_dir(dir).map_err(|error| Error::Io {
+        path: dir.to_path_buf(),
+        error,
+    })?;
+    for entry in entries {
+        let path = entry
+            .map_err(|error| Error::Io {
+                path: dir.to_path_buf(),
+                error,
+            })?
+            .path();
+        if path.is_dir() {
+            find_templates(&path, templates)?;
+        } else if path.extension().is_some_and(|e| e == "ixml") {
+            templates.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Compiles a template into a module implementing `IcedComponent` for its `Model`.
+pub fn compile_template(path: impl AsRef<Path>) -> Result<String> {
+    compile(path.as_ref()).map(|(source, _)| source)
+}
+
+/// Compiles a template, returning the templates it includes or imports along with the code.
+fn compile(path: &Path) -> Result<(String, Vec<PathBuf>)> {
+    let mut template = Template::read(path).map_err(|error| Error::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    let template_error = |message: String| Error::Template {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let parsed = template
+        .parse(&iced_xml_parse::ParseOptions::strict())
+        .map_err(template_error)?;
+    let module = component_module(&parsed.component, &module_name(path))
+        .map_err(|e| template_error(template.describe_error(&e)))?;
+
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let module = iced_xml_codegen::to_source(module).map_err(|e| {
+        template_error(template.snippet(&format!("generated invalid code: {e}"), None))
+    })?;
+    let source = format!("// @generated by iced-xml-build from {file_name}, do not edit\n{module}");
+    Ok((
+        source,
+        parsed.files.into_iter().map(|(_, path)| path).collect(),
+    ))
+}
+
+/// The module a template is compiled into, e.g. `counter_template` for `counter.ixml`.
+fn module_name(path: &Path) -> syn::Ident {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = stem
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    format_ident!("{name}_template")
+}
+
+fn component_module(
+    component: &Component,
+    module: &syn::Ident,
+) -> std::result::Result<TokenStream, TemplateError> {
+    let (model, message) = iced_xml_codegen::component_types(component)?;
+    let Some(model) = model else {
+        return Err(TemplateError::new(
+            "Model",
+            "",
+            "expected a 'Model' attribute on the Component, naming the type to implement IcedComponent for",
+        )
+        .with_spans(&component.spans));
+    };
+    let component_impl = iced_xml_codegen::component_impl(component, &model, &message)?;
+
+    Ok(quote!(
+        mod #module {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #component_impl
+        }
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("iced-xml-build-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn compiles_directory_of_templates() {
+        let ui = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/ui");
+        let out_dir = temp_dir("compile-dir");
+
+        let templates = compile_dir_to(&ui, &out_dir).unwrap();
+
+        assert_eq!(
+            templates,
+            [ui.join("counter.ixml"), ui.join("nested/greeting.ixml")]
+        );
+        let counter = std::fs::read_to_string(out_dir.join("counter.rs")).unwrap();
+        assert!(counter.starts_with("// @generated by iced-xml-build from counter.ixml"));
+        assert!(counter.contains("mod counter_template {"));
+        assert!(counter.contains("impl ::iced_xml_core::IcedComponent for Counter {"));
+        assert!(counter.contains("type Message = Message;"));
+        let greeting = std::fs::read_to_string(out_dir.join("nested/greeting.rs")).unwrap();
+        assert!(greeting.contains("type Message = ();"));
+
+        std::fs::remove_dir_all(out_dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_template_points_at_line() {
+        let dir = temp_dir("invalid");
+        std::fs::write(
+            dir.join("broken.ixml"),
+            "<Component Model=\"Counter\">\n  <Column>\n    <Text Content=\"{self.value +}\" />\n  </Column>\n</Component>",
+        )
+        .unwrap();
+
+        let error = compile_dir_to(&dir, dir.join("out"))
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.starts_with("in attribute 'Content': invalid expression '{self.value +}'"));
+        assert!(error.contains("broken.ixml:3:11"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn error_in_used_template_of_include_points_at_include() {
+        let dir = temp_dir("include");
+        std::fs::write(
+            dir.join("item.ixml"),
+            "<Component>\n  <Template Name=\"Label\" Params=\"value\">\n    <Text Content=\"{value +}\" />\n  </Template>\n  <Use Template=\"Label\" value=\"{self.name}\" />\n</Component>",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("main.ixml"),
+            "<Component Model=\"Main\">\n  <Include Source=\"item.ixml\" />\n</Component>",
+        )
+        .unwrap();
+
+        let error = compile_template(dir.join("main.ixml"))
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.starts_with("in attribute 'Content': invalid expression '{self.name +}'"));
+        assert!(error.contains("item.ixml:3:11"), "{error}");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn included_templates_are_tracked() {
+        let dir = temp_dir("track");
+        let ui = dir.join("ui");
+        std::fs::create_dir_all(&ui).unwrap();
+        std::fs::write(
+            dir.join("header.ixml"),
+            "<Component><Text>Title</Text></Component>",
+        )
+        .unwrap();
+        std::fs::write(
+            ui.join("main.ixml"),
+            "<Component Model=\"Main\"><Include Source=\"../header.ixml\" /></Component>",
+        )
+        .unwrap();
+
+        let mut included = Vec::new();
+        let templates = compile_dir_including(&ui, &dir.join("out"), &mut included).unwrap();
+
+        assert_eq!(templates, [ui.join("main.ixml")]);
+        assert_eq!(included, [dir.join("header.ixml")]);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn template_without_model_complains() {
+        let dir = temp_dir("no-model");
+        std::fs::write(dir.join("anonymous.ixml"), "<Component />").unwrap();
+
+        let error = compile_dir_to(&dir, dir.join("out")).unwrap_err();
+
+        assert!(matches!(error, Error::Template { .. }));
+        assert!(error.to_string().contains("expected a 'Model' attribute"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn compile_dir_outside_build_script_complains() {
+        // Cargo only sets `OUT_DIR` for crates with a build script, which this one has not
+        if std::env::var_os("OUT_DIR").is_none() {
+            assert!(matches!(compile_dir("tests/ui"), Err(Error::MissingOutDir)));
+        }
+    }
+
+    #[test]
+    fn module_names_are_identifiers() {
+        assert_eq!(
+            module_name(Path::new("ui/task-list.ixml")),
+            format_ident!("task_list_template")
+        );
+    }
+}
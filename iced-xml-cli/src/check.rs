@@ -0,0 +1,79 @@
+use std::path::Path;
+
+use iced_xml_parse::ParseOptions;
+
+use crate::template;
+
+/// Checks that a template parses and generates valid code, describing the first error.
+/// Returns the warnings of a lenient parse.
+pub(crate) fn check(path: &Path, options: &ParseOptions) -> Result<Vec<String>, String> {
+    let mut template = template::read(path)?;
+    let parsed = template.parse(options)?;
+    iced_xml_codegen::component_types(&parsed.component)
+        .map_err(|e| template.describe_error(&e))?;
+    iced_xml_codegen::view_component(&parsed.component).map_err(|e| template.describe_error(&e))?;
+    Ok(parsed
+        .warnings
+        .iter()
+        .map(|w| template.describe_parse_error(w))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn valid_template_passes() {
+        let dir = TestDir::new("check-valid");
+        let path = dir.write(
+            "template.ixml",
+            "<Component Message=\"Message\">\n  <Button Message=\"{Increment}\">+</Button>\n</Component>",
+        );
+
+        assert_eq!(check(&path, &ParseOptions::strict()), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn invalid_expression_points_at_line() {
+        let dir = TestDir::new("check-invalid-expr");
+        let path = dir.write(
+            "template.ixml",
+            "<Component>\n  <Text Content=\"{self.}\" />\n</Component>",
+        );
+
+        let error = check(&path, &ParseOptions::strict()).unwrap_err();
+
+        assert!(error.starts_with("in attribute 'Content': invalid expression '{self.}'"));
+        assert!(error.contains(&format!("--> {}:2:9", path.display())));
+    }
+
+    #[test]
+    fn unknown_element_points_at_line() {
+        let dir = TestDir::new("check-unknown");
+        let path = dir.write("template.ixml", "<Component>\n  <Slider />\n</Component>");
+
+        let error = check(&path, &ParseOptions::strict()).unwrap_err();
+
+        assert!(error.starts_with("unknown element 'Slider'"));
+        assert!(error.contains(&format!("--> {}:2:3", path.display())));
+    }
+
+    #[test]
+    fn lenient_check_warns() {
+        let dir = TestDir::new("check-lenient");
+        let path = dir.write(
+            "template.ixml",
+            "<Component>\n  <Column>\n    <Slider />\n    <Text Sise=\"5\">Hi</Text>\n  </Column>\n</Component>",
+        );
+
+        let warnings = check(&path, &ParseOptions::lenient()).unwrap();
+
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("unknown element 'Slider'"));
+        assert!(
+            warnings[1].starts_with("unknown attribute 'Sise' on 'Text', did you mean 'Size'?")
+        );
+    }
+}
@@ -0,0 +1,47 @@
+use std::path::Path;
+
+use iced_xml_parse::ParseOptions;
+
+use crate::template;
+
+/// The rust code generated for a template: an `IcedComponent` impl if it names its `Model`,
+/// otherwise only the `view` method.
+pub(crate) fn expand(path: &Path) -> Result<String, String> {
+    let mut template = template::read(path)?;
+    let component = template.parse(&ParseOptions::strict())?.component;
+    let (model, message) =
+        iced_xml_codegen::component_types(&component).map_err(|e| template.describe_error(&e))?;
+
+    let tokens = match model {
+        Some(model) => iced_xml_codegen::component_impl(&component, &model, &message),
+        None => iced_xml_codegen::view_fn(&component),
+    }
+    .map_err(|e| template.describe_error(&e))?;
+    iced_xml_codegen::to_source(tokens).map_err(|e| format!("generated invalid code: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn expands_component_impl() {
+        let dir = TestDir::new("expand");
+        let path = dir.write(
+            "greeting.ixml",
+            "<Component Model=\"Greeting\">\n  <Text>Hello</Text>\n</Component>",
+        );
+
+        assert_eq!(
+            expand(&path).unwrap(),
+            r#"impl ::iced_xml_core::IcedComponent for Greeting {
+    type Message = ();
+    fn view(&self) -> ::iced::Element<'_, Self::Message> {
+        ::iced::widget::text("Hello").size(10f32).into()
+    }
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use crate::template;
+
+/// Whether formatting changed a template.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Formatted {
+    Unchanged,
+    Changed,
+}
+
+/// Formats a template in place, or only reports whether it would change with `check`.
+pub(crate) fn fmt(path: &Path, check: bool) -> Result<Formatted, String> {
+    let template = template::read(path)?;
+    let formatted = iced_xml_parse::format_str(&template.source)
+        .map_err(|e| template.describe_parse_error(&e))?;
+
+    if formatted == template.source {
+        return Ok(Formatted::Unchanged);
+    }
+    if !check {
+        std::fs::write(path, formatted)
+            .map_err(|e| format!("could not write '{}': {e}", path.display()))?;
+    }
+    Ok(Formatted::Changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_dir::TestDir;
+
+    #[test]
+    fn formats_in_place_unless_checking() {
+        let dir = TestDir::new("fmt");
+        let path = dir.write("template.ixml", "<Component><Text>Hi</Text></Component>");
+
+        assert_eq!(fmt(&path, true), Ok(Formatted::Changed));
+        assert_eq!(fmt(&path, false), Ok(Formatted::Changed));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "<Component>\n  <Text>Hi</Text>\n</Component>\n"
+        );
+        assert_eq!(fmt(&path, true), Ok(Formatted::Unchanged));
+    }
+}
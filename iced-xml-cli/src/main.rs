@@ -0,0 +1,181 @@
+//! `ixml`, checks, formats and expands templates outside of a cargo build.
+mod check;
+mod expand;
+mod fmt;
+mod template;
+#[cfg(test)]
+mod test_dir;
+
+use std::{path::PathBuf, process::ExitCode};
+
+use iced_xml_parse::ParseOptions;
+
+const USAGE: &str = "usage:
+  ixml check [--lenient] <paths>...  report errors in templates, or only warn about
+                                     unknown elements, attributes and invalid values
+  ixml fmt [--check] <paths>...      format templates in place, or list unformatted ones
+  ixml expand <path>                 print the rust code generated for a template
+
+Directories are searched for .ixml files.";
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Check { lenient: bool, paths: Vec<PathBuf> },
+    Fmt { check: bool, paths: Vec<PathBuf> },
+    Expand(PathBuf),
+    Help,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    let command = args.next();
+    let (flags, paths): (Vec<_>, Vec<_>) = args.partition(|arg| arg.starts_with("--"));
+    let paths = paths.into_iter().map(PathBuf::from).collect::<Vec<_>>();
+    let flag = match flags.as_slice() {
+        [] => false,
+        [flag] if flag == "--check" && command.as_deref() == Some("fmt") => true,
+        [flag] if flag == "--lenient" && command.as_deref() == Some("check") => true,
+        [flag, ..] => return Err(format!("unexpected argument '{flag}'")),
+    };
+
+    match command.as_deref() {
+        None | Some("help" | "--help" | "-h") => Ok(Command::Help),
+        Some(_) if paths.is_empty() => Err("expected paths to templates".to_string()),
+        Some("check") => Ok(Command::Check {
+            lenient: flag,
+            paths,
+        }),
+        Some("fmt") => Ok(Command::Fmt { check: flag, paths }),
+        Some("expand") => match <[_; 1]>::try_from(paths) {
+            Ok([path]) => Ok(Command::Expand(path)),
+            Err(_) => Err("expand takes a single template".to_string()),
+        },
+        Some(command) => Err(format!("unknown command '{command}'")),
+    }
+}
+
+fn main() -> ExitCode {
+    let command = match parse_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match command {
+        Command::Help => {
+            println!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Command::Check { lenient, paths } => {
+            let options = if lenient {
+                ParseOptions::lenient()
+            } else {
+                ParseOptions::strict()
+            };
+            let templates = template::collect_templates(&paths);
+            let errors = templates
+                .iter()
+                .filter_map(|path| match check::check(path, &options) {
+                    Ok(warnings) => {
+                        warnings.iter().for_each(|w| eprintln!("warning: {w}\n"));
+                        None
+                    }
+                    Err(e) => Some(e),
+                })
+                .inspect(|e| eprintln!("error: {e}\n"))
+                .count();
+            if errors == 0 {
+                ExitCode::SUCCESS
+            } else {
+                eprintln!("{errors} of {} templates have errors", templates.len());
+                ExitCode::FAILURE
+            }
+        }
+        Command::Fmt { check, paths } => {
+            let mut failed = false;
+            for path in template::collect_templates(&paths) {
+                match fmt::fmt(&path, check) {
+                    Ok(fmt::Formatted::Changed) if check => {
+                        println!("{} is not formatted", path.display());
+                        failed = true;
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        eprintln!("error: {e}\n");
+                        failed = true;
+                    }
+                }
+            }
+            if failed {
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            }
+        }
+        Command::Expand(path) => match expand::expand(&path) {
+            Ok(source) => {
+                print!("{source}");
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("error: {e}");
+                ExitCode::FAILURE
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> Result<Command, String> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn can_parse_commands() {
+        assert_eq!(
+            args("check a.ixml src/ui"),
+            Ok(Command::Check {
+                lenient: false,
+                paths: vec!["a.ixml".into(), "src/ui".into()]
+            })
+        );
+        assert_eq!(
+            args("check --lenient src"),
+            Ok(Command::Check {
+                lenient: true,
+                paths: vec!["src".into()]
+            })
+        );
+        assert_eq!(
+            args("fmt --check src"),
+            Ok(Command::Fmt {
+                check: true,
+                paths: vec!["src".into()]
+            })
+        );
+        assert_eq!(args("expand a.ixml"), Ok(Command::Expand("a.ixml".into())));
+        assert_eq!(args(""), Ok(Command::Help));
+    }
+
+    #[test]
+    fn invalid_arguments_complain() {
+        assert_eq!(
+            args("check --check a.ixml"),
+            Err("unexpected argument '--check'".to_string())
+        );
+        assert_eq!(args("fmt"), Err("expected paths to templates".to_string()));
+        assert_eq!(
+            args("expand a.ixml b.ixml"),
+            Err("expand takes a single template".to_string())
+        );
+        assert_eq!(
+            args("lint a.ixml"),
+            Err("unknown command 'lint'".to_string())
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use std::path::{Path, PathBuf};
+
+use iced_xml_codegen::Template;
+
+/// Reads a template, to describe errors in it like rustc does.
+pub(crate) fn read(path: &Path) -> Result<Template, String> {
+    Template::read(path).map_err(|e| format!("could not read '{}': {e}", path.display()))
+}
+
+/// Expands directories into the templates below them.
+pub(crate) fn collect_templates(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut templates = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            collect_dir(path, &mut templates);
+        } else {
+            templates.push(path.clone());
+        }
+    }
+    templates
+}
+
+fn collect_dir(dir: &Path, templates: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect::<Vec<_>>();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            collect_dir(&path, templates);
+        } else if path.extension().is_some_and(|e| e == "ixml") {
+            templates.push(path);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use std::fmt::Display;
+
+use iced_xml_core::{AttributeSpan, Spans};
+
+/// An error caused by the value of an attribute in a template.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateError {
+    pub attribute: &'static str,
+    pub value: String,
+    pub message: String,
+    /// Where the attribute was written, unless the node was built in code. Boxed to keep
+    /// results small.
+    pub span: Option<Box<AttributeSpan>>,
+}
+
+impl TemplateError {
+    pub fn new(attribute: &'static str, value: &str, message: impl Into<String>) -> Self {
+        Self {
+            attribute,
+            value: value.to_string(),
+            message: message.into(),
+            span: None,
+        }
+    }
+
+    /// Points the error at its attribute in the spans of a node, keeping a span found before.
+    /// `Content` can also be given as the text of the node.
+    pub fn with_spans(mut self, spans: &Spans) -> Self {
+        if self.span.is_none() {
+            let attribute = spans.attribute(self.attribute).cloned().or_else(|| {
+                let text = spans
+                    .text
+                    .as_ref()
+                    .filter(|_| self.attribute == "Content")?;
+                Some(AttributeSpan {
+                    name: self.attribute.to_string(),
+                    span: text.clone(),
+                    value: text.clone(),
+                })
+            });
+            self.span = attribute.map(Box::new);
+        }
+        self
+    }
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "in attribute '{}': {}", self.attribute, self.message)
+    }
+}
+
+impl std::error::Error for TemplateError {}
@@ -0,0 +1,95 @@
+//! Generates the rust code rendering a template. Used by `#[derive(IcedComponent)]`, and by
+//! build scripts and tools which can not depend on a proc macro.
+mod error;
+mod location;
+mod template;
+mod view;
+
+use iced_xml_core::Component;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+pub use error::TemplateError;
+pub use location::Location;
+pub use template::Template;
+pub use view::{
+    is_iced_expr, parse_pattern, qualify_self_message, replace_self_message, trim_iced_expr,
+    view_component_node,
+};
+
+/// The expression rendering the content of a component, an empty column without content.
+pub fn view_component(component: &Component) -> Result<TokenStream, TemplateError> {
+    match &component.content {
+        Some(content) => view_component_node(content),
+        None => Ok(quote!(::iced::widget::column![])),
+    }
+}
+
+/// The `IcedComponent::view` method rendering a component.
+pub fn view_fn(component: &Component) -> Result<TokenStream, TemplateError> {
+    let view = view_component(component)?;
+
+    Ok(quote!(
+        fn view(&self) -> ::iced::Element<'_, Self::Message> {
+            #view
+            .into()
+        }
+    ))
+}
+
+/// Parses the `Model` and `Message` types declared on a component, the message defaulting
+/// to `()`.
+pub fn component_types(
+    component: &Component,
+) -> Result<(Option<syn::Type>, syn::Type), TemplateError> {
+    let parse_type = |attribute: &'static str, ty: &str| {
+        syn::parse_str::<syn::Type>(ty).map_err(|e| {
+            TemplateError::new(attribute, ty, format!("invalid type '{ty}': {e}"))
+                .with_spans(&component.spans)
+        })
+    };
+    let model = component
+        .model_type
+        .as_deref()
+        .map(|model| parse_type("Model", model))
+        .transpose()?;
+    let message = match &component.message_type {
+        Some(message) => parse_type("Message", message)?,
+        None => syn::parse_quote!(()),
+    };
+
+    Ok((model, message))
+}
+
+/// An `IcedComponent` impl rendering the component for `model`.
+pub fn component_impl(
+    component: &Component,
+    model: &syn::Type,
+    message: &syn::Type,
+) -> Result<TokenStream, TemplateError> {
+    let view = view_fn(component)?;
+
+    Ok(quote!(
+        impl ::iced_xml_core::IcedComponent for #model {
+            type Message = #message;
+
+            #view
+        }
+    ))
+}
+
+/// Formats generated items as rust source.
+pub fn to_source(tokens: TokenStream) -> syn::Result<String> {
+    Ok(prettyplease::unparse(&syn::parse2(tokens)?))
+}
+
+/// Formats a generated expression as rust source, as the body of a function, e.g. to compare
+/// generated code regardless of spacing.
+pub fn expr_to_source(tokens: TokenStream) -> syn::Result<String> {
+    let expr = syn::parse2::<syn::Expr>(tokens)?;
+    Ok(prettyplease::unparse(&syn::parse_quote!(
+        fn view() {
+            #expr
+        }
+    )))
+}
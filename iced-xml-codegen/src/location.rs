@@ -0,0 +1,144 @@
+use std::path::{Path, PathBuf};
+
+use iced_xml_core::{FileId, Span};
+
+use crate::TemplateError;
+
+/// A 1-based line and column in a template, spanning `len` characters.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+}
+
+impl Location {
+    /// Where a span of `source` starts, covering the rest of its first line.
+    pub fn of(span: &Span, source: &str) -> Self {
+        let text = source.get(span.range.clone()).unwrap_or_default();
+        Self {
+            line: span.start.line as usize,
+            column: span.start.column as usize,
+            len: text.lines().next().unwrap_or_default().chars().count(),
+        }
+    }
+
+    /// The position of a parser error, which only points at a single character.
+    pub fn at(position: iced_xml_parse::Position) -> Self {
+        Self {
+            line: position.line as usize,
+            column: position.column as usize,
+            len: 1,
+        }
+    }
+}
+
+/// Formats an error like rustc does, showing the template its span points into: the template
+/// itself, named `name`, or one of the `included` templates, whose name and source `load`
+/// returns if it can still be read.
+pub(crate) fn describe_error(
+    error: &TemplateError,
+    name: &str,
+    source: &str,
+    included: &[(FileId, PathBuf)],
+    load: impl FnOnce(&Path) -> Option<(String, String)>,
+) -> String {
+    let message = error.to_string();
+    let Some(attribute) = &error.span else {
+        return snippet(&message, name, source, None);
+    };
+    let span = &attribute.span;
+    match included.iter().find(|(file, _)| *file == span.file) {
+        None => snippet(&message, name, source, Some(Location::of(span, source))),
+        Some((_, path)) => match load(path) {
+            Some((name, source)) => {
+                snippet(&message, &name, &source, Some(Location::of(span, &source)))
+            }
+            None => snippet(&message, &path.display().to_string(), "", None),
+        },
+    }
+}
+
+/// Formats a message about a template like rustc does, pointing at the location if known.
+pub(crate) fn snippet(
+    message: &str,
+    name: &str,
+    source: &str,
+    location: Option<Location>,
+) -> String {
+    match location {
+        None => format!("{message}\n --> {name}"),
+        Some(location) => {
+            let line = source.lines().nth(location.line - 1).unwrap_or("");
+            let number = location.line.to_string();
+            let gutter = " ".repeat(number.len());
+            let indent = " ".repeat(location.column - 1);
+            let marker = "^".repeat(location.len.max(1));
+            format!(
+                "{message}\n{gutter}--> {name}:{}:{}\n{gutter} |\n{number} | {line}\n{gutter} | {indent}{marker}",
+                location.line, location.column
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_in(source: &str) -> TemplateError {
+        let parsed =
+            iced_xml_parse::parse_str_with(source, &iced_xml_parse::ParseOptions::default())
+                .unwrap();
+        crate::view_component(&parsed.component).unwrap_err()
+    }
+
+    #[test]
+    fn locates_attribute_by_span() {
+        let source =
+            "<Component>\n  <Text Size=\"5\" Content=\"{&amp;self.value +}\" />\n</Component>";
+
+        let error = error_in(source);
+
+        assert_eq!(
+            Location::of(&error.span.unwrap().span, source),
+            Location {
+                line: 2,
+                column: 18,
+                len: 29
+            }
+        );
+    }
+
+    #[test]
+    fn describes_error_in_included_template() {
+        let source = "<Component>\n  <Text Content=\"{self.}\" />\n</Component>";
+        let mut error = error_in(source);
+        if let Some(span) = &mut error.span {
+            span.span.file = FileId(1);
+        }
+        let included = [(FileId(1), PathBuf::from("ui/header.ixml"))];
+
+        let described =
+            describe_error(&error, "ui/main.ixml", "<Component />", &included, |path| {
+                Some((path.display().to_string(), source.to_string()))
+            });
+
+        assert_eq!(
+            described,
+            "in attribute 'Content': invalid expression '{self.}': unexpected end of input, expected identifier or integer
+ --> ui/header.ixml:2:9
+  |
+2 |   <Text Content=\"{self.}\" />
+  |         ^^^^^^^^^^^^^^^^^"
+        );
+    }
+
+    #[test]
+    fn snippet_without_location_names_template() {
+        assert_eq!(
+            snippet("invalid xml", "src/test.ixml", "", None),
+            "invalid xml\n --> src/test.ixml"
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+use iced_xml_core::FileId;
+use iced_xml_parse::{ParseOptions, Parsed};
+
+use crate::{
+    Location, TemplateError,
+    location::{describe_error, snippet},
+};
+
+/// The source of a template, kept to describe errors in it like rustc does. Shared by the
+/// derive macro, build scripts and the cli, which only differ in how they report the
+/// descriptions.
+#[derive(Debug, Clone)]
+pub struct Template {
+    /// `None` for templates written inline in rust code
+    pub path: Option<PathBuf>,
+    pub source: String,
+    /// The templates included when it was parsed, which errors can point into
+    pub included: Vec<(FileId, PathBuf)>,
+    display_path: fn(&Path) -> String,
+}
+
+impl Template {
+    /// Reads and decodes a template file. Templates which can not be decoded are reported as
+    /// invalid data.
+    pub fn read(path: &Path) -> std::io::Result<Self> {
+        let bytes = std::fs::read(path)?;
+        let source = iced_xml_parse::decode(&bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+
+        Ok(Self {
+            path: Some(path.to_path_buf()),
+            source,
+            included: Vec::new(),
+            display_path: |path| path.display().to_string(),
+        })
+    }
+
+    /// A template given as a string, e.g. a literal in rust code.
+    pub fn inline(source: String) -> Self {
+        Self {
+            path: None,
+            source,
+            included: Vec::new(),
+            display_path: |path| path.display().to_string(),
+        }
+    }
+
+    /// Shows the paths of the template and the templates it includes with `display_path`,
+    /// e.g. relative to the crate.
+    pub fn with_display_path(self, display_path: fn(&Path) -> String) -> Self {
+        Self {
+            display_path,
+            ..self
+        }
+    }
+
+    /// How the template is referred to in messages.
+    pub fn name(&self) -> String {
+        match &self.path {
+            Some(path) => (self.display_path)(path),
+            None => "inline template".to_string(),
+        }
+    }
+
+    /// Parses the template, resolving includes relative to its file and remembering them to
+    /// show errors in them.
+    pub fn parse(&mut self, options: &ParseOptions) -> Result<Parsed, String> {
+        let options = match &self.path {
+            Some(path) => options.clone().with_path(path),
+            None => options.clone(),
+        };
+        let parsed = iced_xml_parse::parse_str_with(&self.source, &options)
+            .map_err(|e| self.describe_parse_error(&e))?;
+        self.included = parsed.files.clone();
+        Ok(parsed)
+    }
+
+    /// Describes an error or warning of the parser, pointing at its position.
+    pub fn describe_parse_error(&self, error: &iced_xml_parse::Error) -> String {
+        let location = error.position().map(Location::at);
+        self.snippet(&error.to_string(), location)
+    }
+
+    /// Describes an error of the generated code, pointing into the template or the included
+    /// template its span is in.
+    pub fn describe_error(&self, error: &TemplateError) -> String {
+        describe_error(error, &self.name(), &self.source, &self.included, |path| {
+            let included = Template::read(path).ok()?;
+            Some(((self.display_path)(path), included.source))
+        })
+    }
+
+    /// Formats a message about the template, pointing at the location if known.
+    pub fn snippet(&self, message: &str, location: Option<Location>) -> String {
+        snippet(message, &self.name(), &self.source, location)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_points_at_position() {
+        let mut template = Template {
+            path: Some(PathBuf::from("ui/test.ixml")),
+            ..Template::inline("<Component>\n  <Slider />\n</Component>".to_string())
+        };
+
+        assert_eq!(
+            template.parse(&ParseOptions::strict()).unwrap_err(),
+            "unknown element 'Slider'
+ --> ui/test.ixml:2:3
+  |
+2 |   <Slider />
+  |   ^"
+        );
+    }
+
+    #[test]
+    fn inline_template_is_named() {
+        let template = Template::inline("<Component />".to_string());
+
+        assert_eq!(
+            template.snippet("invalid", None),
+            "invalid\n --> inline template"
+        );
+    }
+}
@@ -0,0 +1,500 @@
+use iced_xml_core::{
+    ButtonNode, Color, ColumnNode, ComponentNode, Font, HorizontalAlignment, Length, MatchNode,
+    NodeLayout, RowNode, TextNode, VerticalAlignment,
+};
+use proc_macro2::{Group, TokenStream, TokenTree};
+use quote::quote;
+
+use crate::TemplateError;
+
+type Result<T> = std::result::Result<T, TemplateError>;
+
+pub fn is_iced_expr(expr: &str) -> bool {
+    expr.starts_with("{") && expr.ends_with("}")
+}
+
+pub fn trim_iced_expr(expr: &str) -> &str {
+    expr.strip_prefix('{')
+        .and_then(|expr| expr.strip_suffix('}'))
+        .unwrap_or(expr)
+}
+
+fn parse_expr(attribute: &'static str, expr: &str) -> Result<syn::Expr> {
+    if is_iced_expr(expr) {
+        syn::parse_str::<syn::Expr>(trim_iced_expr(expr)).map_err(|e| {
+            TemplateError::new(attribute, expr, format!("invalid expression '{expr}': {e}"))
+        })
+    } else {
+        Err(TemplateError::new(
+            attribute,
+            expr,
+            format!("expected an expression in braces, e.g. '{{{expr}}}'"),
+        ))
+    }
+}
+
+fn parse_expr_or_lit(attribute: &'static str, expr_or_lit: &str) -> Result<syn::Expr> {
+    if is_iced_expr(expr_or_lit) {
+        parse_expr(attribute, expr_or_lit)
+    } else {
+        let lit = syn::LitStr::new(expr_or_lit, proc_macro2::Span::call_site());
+        Ok(syn::parse_quote!(#lit))
+    }
+}
+
+pub fn view_component_node(node: &ComponentNode) -> Result<TokenStream> {
+    view_node(node).map_err(|e| e.with_spans(&node.spans))
+}
+
+fn view_node(node: &ComponentNode) -> Result<TokenStream> {
+    let content = match &node.node {
+        iced_xml_core::Node::Button(button_node) => view_button_node(button_node)?,
+        iced_xml_core::Node::Text(text_node) => view_text_node(text_node)?,
+        iced_xml_core::Node::Column(column_node) => view_column_node(column_node)?,
+        iced_xml_core::Node::Row(row_node) => view_row_node(row_node)?,
+        iced_xml_core::Node::Match(match_node) => view_match_node(match_node)?,
+        // Unknown elements parsed leniently, which have been warned about, render nothing
+        iced_xml_core::Node::Placeholder(_) => quote!(::iced::widget::column![]),
+    };
+
+    Ok(view_layout(node.layout, content))
+}
+
+/// Wraps a node in a container aligning, padding and sizing it, unless it has the default
+/// layout. Aligned nodes fill the space given by their parent to be aligned within it, unless
+/// they are given a size.
+fn view_layout(layout: NodeLayout, content: TokenStream) -> TokenStream {
+    if layout == NodeLayout::default() {
+        return content;
+    }
+
+    let width = view_length(layout.width);
+    let height = view_length(layout.height);
+    let aligned = |length: Length| match length {
+        Length::Shrink => quote!(::iced::Length::Fill),
+        length => view_length(length),
+    };
+    let horizontal = match layout.align_x {
+        HorizontalAlignment::Left => {
+            (layout.width != Length::Shrink).then(|| quote!(.width(#width)))
+        }
+        HorizontalAlignment::Center => {
+            let width = aligned(layout.width);
+            Some(quote!(.center_x(#width)))
+        }
+        HorizontalAlignment::Right => {
+            let width = aligned(layout.width);
+            Some(quote!(.align_right(#width)))
+        }
+        HorizontalAlignment::Stretch => Some(quote!(.width(::iced::Length::Fill))),
+    };
+    let vertical = match layout.align_y {
+        VerticalAlignment::Top => {
+            (layout.height != Length::Shrink).then(|| quote!(.height(#height)))
+        }
+        VerticalAlignment::Center => {
+            let height = aligned(layout.height);
+            Some(quote!(.center_y(#height)))
+        }
+        VerticalAlignment::Bottom => {
+            let height = aligned(layout.height);
+            Some(quote!(.align_bottom(#height)))
+        }
+        VerticalAlignment::Stretch => Some(quote!(.height(::iced::Length::Fill))),
+    };
+    let padding = layout.padding.value;
+    let padding = (padding != 0.0).then(|| quote!(.padding(#padding)));
+
+    quote!(
+        ::iced::widget::container(#content)
+        #horizontal
+        #vertical
+        #padding
+    )
+}
+
+fn view_length(length: Length) -> TokenStream {
+    match length {
+        Length::Fill => quote!(::iced::Length::Fill),
+        Length::FillPortion(portion) => quote!(::iced::Length::FillPortion(#portion)),
+        Length::Shrink => quote!(::iced::Length::Shrink),
+        Length::Fixed(pixels) => quote!(::iced::Length::Fixed(#pixels)),
+    }
+}
+
+fn view_color(color: Color) -> TokenStream {
+    let Color { r, g, b, a } = color;
+    quote!(::iced::Color::from_rgba(#r, #g, #b, #a))
+}
+
+fn view_font(font: &Font) -> TokenStream {
+    let family = match font {
+        Font::Default => return quote!(::iced::Font::DEFAULT),
+        Font::Monospace => return quote!(::iced::Font::MONOSPACE),
+        Font::Serif => quote!(::iced::font::Family::Serif),
+        Font::SansSerif => quote!(::iced::font::Family::SansSerif),
+        Font::Named(name) => return quote!(::iced::Font::with_name(#name)),
+    };
+    quote!(::iced::Font {
+        family: #family,
+        ..::iced::Font::DEFAULT
+    })
+}
+
+fn view_button_node(node: &ButtonNode) -> Result<TokenStream> {
+    let content = parse_expr_or_lit("Content", &node.content)?;
+    let on_press = if node.message.is_empty() {
+        None
+    } else {
+        let message = syn::parse_str::<syn::Expr>(trim_iced_expr(&node.message)).map_err(|e| {
+            TemplateError::new(
+                "Message",
+                &node.message,
+                format!("invalid message '{}': {e}", node.message),
+            )
+        })?;
+        Some(quote!(.on_press(Self::Message::#message)))
+    };
+
+    Ok(quote!(
+        ::iced::widget::button(#content)
+        #on_press
+    ))
+}
+
+fn view_text_node(node: &TextNode) -> Result<TokenStream> {
+    let content = parse_expr_or_lit("Content", &node.content)?;
+    let size = node.size;
+    let color = node.color.map(|color| {
+        let color = view_color(color);
+        quote!(.color(#color))
+    });
+    let font = node.font.as_ref().map(|font| {
+        let font = view_font(font);
+        quote!(.font(#font))
+    });
+
+    Ok(quote!(
+        ::iced::widget::text(#content)
+        .size(#size)
+        #color
+        #font
+    ))
+}
+
+fn view_column_node(node: &ColumnNode) -> Result<TokenStream> {
+    let children = node
+        .content
+        .iter()
+        .map(|c| view_component_node(c))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(quote!(::iced::widget::column![
+        #(#children),*
+    ]))
+}
+
+fn view_row_node(node: &RowNode) -> Result<TokenStream> {
+    let children = node
+        .content
+        .iter()
+        .map(|c| view_component_node(c))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(quote!(::iced::widget::row![
+        #(#children),*
+    ]))
+}
+
+pub fn parse_pattern(case: &str, destructure: Option<&str>) -> Result<syn::Pat> {
+    let pattern = match destructure {
+        Some(destructure) => format!("{case}{}", trim_iced_expr(destructure)),
+        None => case.to_string(),
+    };
+
+    syn::parse::Parser::parse_str(syn::Pat::parse_multi, &pattern).map_err(|e| match destructure {
+        Some(destructure) => TemplateError::new(
+            "Destructure",
+            destructure,
+            format!("invalid pattern '{pattern}': {e}"),
+        ),
+        None => TemplateError::new("Case", case, format!("invalid pattern '{pattern}': {e}")),
+    })
+}
+
+fn view_match_node(node: &MatchNode) -> Result<TokenStream> {
+    let value = parse_expr("Value", &node.value)?;
+    let arms = node
+        .cases
+        .iter()
+        .map(|case| {
+            let pattern = parse_pattern(&case.case, case.destructure.as_deref())
+                .map_err(|e| e.with_spans(&case.spans))?;
+            let content = match &case.content {
+                Some(content) => view_component_node(content)?,
+                None => quote!(::iced::widget::column![]),
+            };
+
+            Ok(quote!(
+                #[allow(unused_variables)]
+                #pattern => ::iced::Element::<'_, Self::Message>::from(#content),
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(quote!(match #value {
+        #(#arms)*
+    }))
+}
+
+/// Replaces `Self::Message` with `<Self as IcedComponent>::Message`, for views generated
+/// outside of the `IcedComponent` impl.
+pub fn qualify_self_message(tokens: TokenStream) -> TokenStream {
+    replace_self_message(
+        tokens,
+        &quote!(<Self as ::iced_xml_core::IcedComponent>::Message),
+    )
+}
+
+/// Replaces `Self::Message` with another message type.
+pub fn replace_self_message(tokens: TokenStream, message: &TokenStream) -> TokenStream {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut qualified = Vec::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(group) => {
+                let mut inner = Group::new(
+                    group.delimiter(),
+                    replace_self_message(group.stream(), message),
+                );
+                inner.set_span(group.span());
+                qualified.push(TokenTree::Group(inner));
+            }
+            TokenTree::Ident(ident) if ident == "Self" => {
+                let rest = tokens
+                    .clone()
+                    .take(3)
+                    .map(|t| t.to_string())
+                    .collect::<Vec<_>>();
+                if rest == [":", ":", "Message"] {
+                    tokens.nth(2);
+                    qualified.extend(message.clone());
+                } else {
+                    qualified.push(TokenTree::Ident(ident));
+                }
+            }
+            token => qualified.push(token),
+        }
+    }
+    qualified.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use iced_xml_core::Node;
+
+    use crate::expr_to_source;
+
+    use super::*;
+
+    #[test]
+    fn can_parse_button_content_expr() {
+        let node = ButtonNode {
+            content: "{self.value}".to_string(),
+            message: String::new(),
+        };
+
+        let parsed = view_button_node(&node).unwrap();
+        let expected = quote!(::iced::widget::button(self.value));
+
+        assert_eq!(
+            expr_to_source(parsed).unwrap(),
+            expr_to_source(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn can_parse_button_content_ident() {
+        let node = ButtonNode {
+            content: "Click me".to_string(),
+            message: String::new(),
+        };
+
+        let parsed = view_button_node(&node).unwrap();
+        let expected = quote!(::iced::widget::button("Click me"));
+
+        assert_eq!(
+            expr_to_source(parsed).unwrap(),
+            expr_to_source(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn can_parse_button_message() {
+        let node = ButtonNode {
+            content: "Click me".to_string(),
+            message: "{Increment}".to_string(),
+        };
+
+        let parsed = view_button_node(&node).unwrap();
+        let expected =
+            quote!(::iced::widget::button("Click me").on_press(Self::Message::Increment));
+
+        assert_eq!(
+            expr_to_source(parsed).unwrap(),
+            expr_to_source(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn can_parse_match_destructure() {
+        let node = MatchNode {
+            value: "{&self.filter}".to_string(),
+            cases: vec![
+                iced_xml_core::MatchCaseNode {
+                    case: "Some".to_string(),
+                    destructure: Some("{(Filter { name, .. })}".to_string()),
+                    content: None,
+                    spans: iced_xml_core::Spans::default(),
+                },
+                iced_xml_core::MatchCaseNode {
+                    case: "None".to_string(),
+                    destructure: None,
+                    content: None,
+                    spans: iced_xml_core::Spans::default(),
+                },
+            ],
+        };
+
+        let parsed = view_match_node(&node).unwrap();
+        let expected = quote!(match &self.filter {
+            #[allow(unused_variables)]
+            Some(Filter { name, .. }) =>
+                ::iced::Element::<'_, Self::Message>::from(::iced::widget::column![]),
+            #[allow(unused_variables)]
+            None => ::iced::Element::<'_, Self::Message>::from(::iced::widget::column![]),
+        });
+
+        assert_eq!(
+            expr_to_source(parsed).unwrap(),
+            expr_to_source(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_button_content_complains() {
+        let node = ButtonNode {
+            content: "{self.value +}".to_string(),
+            message: String::new(),
+        };
+
+        let error = view_button_node(&node).unwrap_err();
+
+        assert_eq!(error.attribute, "Content");
+        assert_eq!(error.value, "{self.value +}");
+    }
+
+    #[test]
+    fn errors_point_at_attribute_or_text() {
+        let source =
+            "<Column>\n  <Button Message=\"{Add(}\">+</Button>\n  <Text>{self.}</Text>\n</Column>";
+        let mut component =
+            iced_xml_parse::parse_str(&format!("<Component>{source}</Component>")).unwrap();
+        let Some(iced_xml_core::ComponentNode {
+            node: iced_xml_core::Node::Column(column),
+            ..
+        }) = component.content.as_mut()
+        else {
+            panic!("expected a column");
+        };
+
+        let message = view_component_node(&column.content[0]).unwrap_err();
+        let span = message.span.unwrap();
+        assert_eq!(span.name, "Message");
+        assert_eq!((span.value.start.line, span.value.start.column), (2, 20));
+
+        column.content.remove(0);
+        let content = view_component_node(&column.content[0]).unwrap_err();
+        let span = content.span.unwrap();
+        assert_eq!(content.attribute, "Content");
+        assert_eq!((span.value.start.line, span.value.start.column), (3, 9));
+    }
+
+    #[test]
+    fn text_content_without_braces_is_literal() {
+        let node = TextNode {
+            content: "Say \"hi\"".to_string(),
+            size: 10.0,
+            color: None,
+            font: None,
+        };
+
+        let parsed = view_text_node(&node).unwrap();
+        let expected = quote!(::iced::widget::text("Say \"hi\"").size(10f32));
+
+        assert_eq!(
+            expr_to_source(parsed).unwrap(),
+            expr_to_source(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn text_has_color_font_and_size() {
+        let node = ComponentNode {
+            node: Node::Text(TextNode {
+                content: "Hi".to_string(),
+                size: 10.0,
+                color: Some(Color::BLACK),
+                font: Some(Font::Serif),
+            }),
+            layout: NodeLayout {
+                align_x: HorizontalAlignment::Center,
+                width: Length::Fixed(100.0),
+                height: Length::Fill,
+                ..NodeLayout::default()
+            },
+            spans: Default::default(),
+        };
+
+        let parsed = view_node(&node).unwrap();
+        let expected = quote!(
+            ::iced::widget::container(
+                ::iced::widget::text("Hi")
+                    .size(10f32)
+                    .color(::iced::Color::from_rgba(0f32, 0f32, 0f32, 1f32))
+                    .font(::iced::Font {
+                        family: ::iced::font::Family::Serif,
+                        ..::iced::Font::DEFAULT
+                    })
+            )
+            .center_x(::iced::Length::Fixed(100f32))
+            .height(::iced::Length::Fill)
+        );
+
+        assert_eq!(
+            expr_to_source(parsed).unwrap(),
+            expr_to_source(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn can_qualify_self_message() {
+        let tokens = quote!(::iced::widget::column![
+            ::iced::widget::button("+").on_press(Self::Message::Increment),
+            Self::Other
+        ]);
+
+        let expected = quote!(::iced::widget::column![
+            ::iced::widget::button("+")
+                .on_press(<Self as ::iced_xml_core::IcedComponent>::Message::Increment),
+            Self::Other
+        ]);
+
+        assert_eq!(
+            qualify_self_message(tokens).to_string(),
+            expected.to_string()
+        );
+    }
+}
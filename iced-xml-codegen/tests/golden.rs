@@ -0,0 +1,56 @@
+//! Compares the code generated for the templates in `tests/golden` with the `.rs` file next
+//! to them. Run with `UPDATE_GOLDEN=1` to accept changes to the generated code.
+use std::path::PathBuf;
+
+fn check_golden(name: &str) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let component = iced_xml_parse::parse_file(dir.join(format!("{name}.ixml"))).unwrap();
+    let generated =
+        iced_xml_codegen::to_source(iced_xml_codegen::view_fn(&component).unwrap()).unwrap();
+
+    let golden = dir.join(format!("{name}.rs"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&golden, &generated).unwrap();
+    }
+    let expected = std::fs::read_to_string(&golden).unwrap_or_default();
+
+    assert_eq!(
+        generated, expected,
+        "generated code for {name}.ixml changed, run with UPDATE_GOLDEN=1 to accept it"
+    );
+}
+
+#[test]
+fn golden_empty() {
+    check_golden("empty");
+}
+
+#[test]
+fn golden_button() {
+    check_golden("button");
+}
+
+#[test]
+fn golden_text() {
+    check_golden("text");
+}
+
+#[test]
+fn golden_column() {
+    check_golden("column");
+}
+
+#[test]
+fn golden_row() {
+    check_golden("row");
+}
+
+#[test]
+fn golden_match() {
+    check_golden("match");
+}
+
+#[test]
+fn golden_layout() {
+    check_golden("layout");
+}
@@ -0,0 +1,3 @@
+fn view(&self) -> ::iced::Element<'_, Self::Message> {
+    ::iced::widget::button("Increment").on_press(Self::Message::Increment).into()
+}
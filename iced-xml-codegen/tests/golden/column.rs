@@ -0,0 +1,12 @@
+fn view(&self) -> ::iced::Element<'_, Self::Message> {
+    ::iced::widget::container(
+            ::iced::widget::column![
+                ::iced::widget::button("Increment").on_press(Self::Message::Increment),
+                ::iced::widget::text(self.value).size(50f32),
+                ::iced::widget::button("Decrement").on_press(Self::Message::Decrement)
+            ],
+        )
+        .center_x(::iced::Length::Fill)
+        .padding(20f32)
+        .into()
+}
@@ -0,0 +1,14 @@
+fn view(&self) -> ::iced::Element<'_, Self::Message> {
+    ::iced::widget::container(
+            ::iced::widget::row![
+                ::iced::widget::container(::iced::widget::text("Right").size(10f32))
+                .align_right(::iced::Length::Fill),
+                ::iced::widget::container(::iced::widget::button("Save")
+                .on_press(Self::Message::Save)).align_bottom(::iced::Length::Fill)
+                .padding(4f32), ::iced::widget::container(::iced::widget::text("Fill")
+                .size(10f32)).width(::iced::Length::Fill).center_y(::iced::Length::Fill)
+            ],
+        )
+        .height(::iced::Length::Fill)
+        .into()
+}
@@ -0,0 +1,19 @@
+fn view(&self) -> ::iced::Element<'_, Self::Message> {
+    match &self.user {
+        #[allow(unused_variables)]
+        Some(user) => {
+            ::iced::Element::<
+                '_,
+                Self::Message,
+            >::from(::iced::widget::text(&user.name).size(10f32))
+        }
+        #[allow(unused_variables)]
+        None => {
+            ::iced::Element::<
+                '_,
+                Self::Message,
+            >::from(::iced::widget::button("Log in").on_press(Self::Message::LogIn))
+        }
+    }
+        .into()
+}
@@ -0,0 +1,7 @@
+fn view(&self) -> ::iced::Element<'_, Self::Message> {
+    ::iced::widget::row![
+        ::iced::widget::text("Name").size(10f32), ::iced::widget::button("Clear")
+        .on_press(Self::Message::Clear)
+    ]
+        .into()
+}
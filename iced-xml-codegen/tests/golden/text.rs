@@ -0,0 +1,3 @@
+fn view(&self) -> ::iced::Element<'_, Self::Message> {
+    ::iced::widget::text(format!("{} items", self.items.len())).size(24f32).into()
+}
@@ -0,0 +1,33 @@
+use std::fmt::Display;
+
+/// An attribute value which does not parse into its type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseValueError {
+    /// What the value should have been, e.g. "horizontal alignment"
+    pub kind: &'static str,
+    pub value: String,
+    /// The accepted values, empty for values which are not a fixed set, like numbers
+    pub expected: &'static [&'static str],
+}
+
+impl ParseValueError {
+    pub fn new(kind: &'static str, value: &str, expected: &'static [&'static str]) -> Self {
+        Self {
+            kind,
+            value: value.to_string(),
+            expected,
+        }
+    }
+}
+
+impl Display for ParseValueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid {} '{}'", self.kind, self.value)?;
+        if !self.expected.is_empty() {
+            write!(f, ", expected one of: {}", self.expected.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseValueError {}
@@ -1,4 +1,135 @@
 mod component;
+mod error;
 mod node;
+pub mod reflect;
+pub mod reload;
+mod span;
 pub use component::IcedComponent;
+pub use error::ParseValueError;
 pub use node::*;
+pub use span::{AttributeSpan, FileId, LineColumn, Span, Spans};
+
+#[doc(hidden)]
+pub mod __private {
+    //! Support for code generated by `#[derive(IcedComponent)]`.
+    use crate::reflect::{Error, Value};
+
+    /// Wraps a field, so its value can be reflected if it converts into a [`Value`].
+    ///
+    /// Calling `(&Wrap(&field)).to_value()` with both traits in scope resolves to
+    /// [`ToValue`] if the field converts and falls back to [`ToNone`] otherwise.
+    pub struct Wrap<'a, T>(pub &'a T);
+
+    pub trait ToValue {
+        fn to_value(&self) -> Option<Result<Value, Error>>;
+    }
+
+    impl<T> ToValue for Wrap<'_, T>
+    where
+        T: Clone + TryInto<Value>,
+        T::Error: Into<Error>,
+    {
+        fn to_value(&self) -> Option<Result<Value, Error>> {
+            Some(self.0.clone().try_into().map_err(Into::into))
+        }
+    }
+
+    pub trait ToNone {
+        fn to_value(&self) -> Option<Result<Value, Error>>;
+    }
+
+    impl<T> ToNone for &Wrap<'_, T> {
+        fn to_value(&self) -> Option<Result<Value, Error>> {
+            None
+        }
+    }
+
+    /// Wraps a message variant taking arguments, so it can be constructed from [`Value`]s if
+    /// all of its fields convert from one.
+    ///
+    /// Calling `(&Variant(f)).construct(args)` with `f: fn(..) -> M` and both traits in scope
+    /// resolves to [`Construct`] if the fields convert and falls back to [`NotConstructible`]
+    /// otherwise.
+    pub struct Variant<F>(pub F);
+
+    pub trait Construct<M> {
+        fn construct(&self, args: &[Value]) -> Option<M>;
+    }
+
+    macro_rules! impl_construct {
+        ($(($($ty:ident $arg:ident),+)),*) => {
+            $(impl<M, $($ty: TryFrom<Value>),+> Construct<M> for Variant<fn($($ty),+) -> M> {
+                fn construct(&self, args: &[Value]) -> Option<M> {
+                    let [$($arg),+] = args else {
+                        return None;
+                    };
+                    Some((self.0)($($ty::try_from($arg.clone()).ok()?),+))
+                }
+            })*
+        };
+    }
+    impl_construct!(
+        (A a),
+        (A a, B b),
+        (A a, B b, C c),
+        (A a, B b, C c, D d),
+        (A a, B b, C c, D d, E e),
+        (A a, B b, C c, D d, E e, F f)
+    );
+
+    pub trait NotConstructible<M> {
+        fn construct(&self, args: &[Value]) -> Option<M>;
+    }
+
+    impl<F, M> NotConstructible<M> for &Variant<F> {
+        fn construct(&self, _args: &[Value]) -> Option<M> {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::__private::{Construct, NotConstructible, ToNone, ToValue, Variant, Wrap};
+    use super::reflect::{Error, Value};
+
+    #[test]
+    #[allow(clippy::needless_borrow)]
+    fn wrap_reflects_convertible_fields_only() {
+        struct Opaque;
+
+        assert_eq!((&Wrap(&42_i64)).to_value(), Some(Ok(Value::Int(42))));
+        assert_eq!((&Wrap(&42_usize)).to_value(), Some(Ok(Value::Int(42))));
+        assert_eq!(
+            (&Wrap(&u64::MAX)).to_value(),
+            Some(Err(Error::IntOutOfRange(u64::MAX.to_string())))
+        );
+        assert_eq!((&Wrap(&Opaque)).to_value(), None);
+    }
+
+    #[test]
+    #[allow(clippy::needless_borrow)]
+    fn variant_constructs_from_convertible_fields_only() {
+        #[derive(Debug, PartialEq)]
+        struct Opaque;
+        #[derive(Debug, PartialEq)]
+        enum Message {
+            Toggle(usize, bool),
+            Other(Opaque),
+        }
+
+        let toggle: fn(_, _) -> _ = Message::Toggle;
+        assert_eq!(
+            (&Variant(toggle)).construct(&[Value::Int(1), Value::Bool(true)]),
+            Some(Message::Toggle(1, true))
+        );
+        assert_eq!(
+            (&Variant(toggle)).construct(&[Value::Int(-1), Value::Bool(true)]),
+            None
+        );
+        assert_eq!((&Variant(toggle)).construct(&[Value::Int(1)]), None);
+
+        let other: fn(_) -> _ = Message::Other;
+        assert_eq!((&Variant(other)).construct(&[Value::Unit]), None::<Message>);
+    }
+}
@@ -1,101 +1,573 @@
-#[derive(Debug, Copy, Clone, Default)]
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::{Mutex, PoisonError};
+
+use crate::{ParseValueError, Spans};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
 pub struct NodeLayout {
     pub align_x: HorizontalAlignment,
     pub align_y: VerticalAlignment,
     pub padding: Padding,
+    pub width: Length,
+    pub height: Length,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ComponentNode {
     pub node: Node,
     pub layout: NodeLayout,
+    pub spans: Spans,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Node {
     Button(ButtonNode),
     Text(TextNode),
     Column(ColumnNode),
     Row(RowNode),
+    Match(MatchNode),
+    Placeholder(PlaceholderNode),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Component {
     pub content: Option<ComponentNode>,
     pub message_type: Option<String>,
     pub model_type: Option<String>,
+    /// The templates defined in the component, already expanded where they are used
+    pub templates: Vec<TemplateNode>,
+    /// The parameters declared in the `Props` of the component
+    pub props: Vec<PropNode>,
+    pub spans: Spans,
 }
 
-#[derive(Debug, Clone)]
+impl Component {
+    /// Clears the spans of the component and all of its nodes, to compare it with a component
+    /// written elsewhere or built in code.
+    pub fn strip_spans(&mut self) {
+        self.spans = Spans::default();
+        if let Some(content) = &mut self.content {
+            content.strip_spans();
+        }
+        for template in &mut self.templates {
+            template.spans = Spans::default();
+            if let Some(content) = &mut template.content {
+                content.strip_spans();
+            }
+        }
+        for prop in &mut self.props {
+            prop.spans = Spans::default();
+        }
+    }
+}
+
+impl ComponentNode {
+    /// Clears the spans of the node and its children.
+    pub fn strip_spans(&mut self) {
+        self.spans = Spans::default();
+        match &mut self.node {
+            Node::Button(_) | Node::Text(_) | Node::Placeholder(_) => {}
+            Node::Column(ColumnNode { content }) | Node::Row(RowNode { content }) => {
+                content.iter_mut().for_each(|c| c.strip_spans())
+            }
+            Node::Match(match_node) => {
+                for case in &mut match_node.cases {
+                    case.spans = Spans::default();
+                    if let Some(content) = &mut case.content {
+                        content.strip_spans();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct ButtonNode {
     pub content: String, // TODO: Make more general
     pub message: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TextNode {
     pub content: String,
     pub size: f32,
+    /// `None` to use the color of the theme
+    pub color: Option<Color>,
+    /// `None` to use the default font of the application
+    pub font: Option<Font>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ColumnNode {
     pub content: Vec<Box<ComponentNode>>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct RowNode {
     pub content: Vec<Box<ComponentNode>>,
 }
 
-#[derive(Debug, Copy, Clone)]
+/// Renders the content of the first case whose pattern matches `value`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchNode {
+    pub value: String,
+    pub cases: Vec<MatchCaseNode>,
+}
+
+/// A case of a [`MatchNode`]. The pattern of the case is `case` followed by `destructure`,
+/// e.g. `Case="Some" Destructure="(value)"`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchCaseNode {
+    pub case: String,
+    pub destructure: Option<String>,
+    pub content: Option<Box<ComponentNode>>,
+    pub spans: Spans,
+}
+
+/// A reusable part of a component, expanded by `Use` elements with an argument for each of its
+/// parameters.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateNode {
+    pub name: String,
+    pub params: Vec<String>,
+    pub content: Option<Box<ComponentNode>>,
+    pub spans: Spans,
+}
+
+/// A parameter of a component, e.g. `<Prop Name="label" Type="String" Default="Hello" />`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropNode {
+    pub name: String,
+    /// A rust type
+    pub ty: String,
+    /// An expression in braces, or text for a string
+    pub default: Option<String>,
+    pub spans: Spans,
+}
+
+/// An element the parser does not know, kept when parsing leniently so that the rest of the
+/// template can still be shown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaceholderNode {
+    pub name: String,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
 pub enum HorizontalAlignment {
+    #[default]
     Left,
     Center,
     Right,
     Stretch,
 }
-impl Default for HorizontalAlignment {
-    fn default() -> Self {
-        Self::Left
-    }
+impl HorizontalAlignment {
+    pub const VALUES: &[&str] = &["Left", "Center", "Right", "Stretch"];
 }
-impl<'a> From<&str> for HorizontalAlignment {
-    fn from(value: &str) -> Self {
+impl FromStr for HorizontalAlignment {
+    type Err = ParseValueError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
         match value.to_lowercase().as_str() {
-            "left" => HorizontalAlignment::Left,
-            "center" => HorizontalAlignment::Center,
-            "right" => HorizontalAlignment::Right,
-            "stretch" => HorizontalAlignment::Stretch,
-            _ => HorizontalAlignment::default(),
+            "left" => Ok(HorizontalAlignment::Left),
+            "center" => Ok(HorizontalAlignment::Center),
+            "right" => Ok(HorizontalAlignment::Right),
+            "stretch" => Ok(HorizontalAlignment::Stretch),
+            _ => Err(ParseValueError::new(
+                "horizontal alignment",
+                value,
+                Self::VALUES,
+            )),
         }
     }
 }
-#[derive(Debug, Copy, Clone)]
+/// Lossy: unknown values become the default, parse the value to reject them instead.
+impl From<&str> for HorizontalAlignment {
+    fn from(value: &str) -> Self {
+        value.parse().unwrap_or_default()
+    }
+}
+impl Display for HorizontalAlignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            HorizontalAlignment::Left => "Left",
+            HorizontalAlignment::Center => "Center",
+            HorizontalAlignment::Right => "Right",
+            HorizontalAlignment::Stretch => "Stretch",
+        })
+    }
+}
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
 pub enum VerticalAlignment {
+    #[default]
     Top,
     Center,
     Bottom,
     Stretch,
 }
-impl Default for VerticalAlignment {
-    fn default() -> Self {
-        Self::Top
-    }
+impl VerticalAlignment {
+    pub const VALUES: &[&str] = &["Top", "Center", "Bottom", "Stretch"];
 }
-impl<'a> From<&str> for VerticalAlignment {
-    fn from(value: &str) -> Self {
+impl FromStr for VerticalAlignment {
+    type Err = ParseValueError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
         match value.to_lowercase().as_str() {
-            "top" => VerticalAlignment::Top,
-            "center" => VerticalAlignment::Center,
-            "bottom" => VerticalAlignment::Bottom,
-            "stretch" => VerticalAlignment::Stretch,
-            _ => VerticalAlignment::default(),
+            "top" => Ok(VerticalAlignment::Top),
+            "center" => Ok(VerticalAlignment::Center),
+            "bottom" => Ok(VerticalAlignment::Bottom),
+            "stretch" => Ok(VerticalAlignment::Stretch),
+            _ => Err(ParseValueError::new(
+                "vertical alignment",
+                value,
+                Self::VALUES,
+            )),
         }
     }
 }
+/// Lossy: unknown values become the default, parse the value to reject them instead.
+impl From<&str> for VerticalAlignment {
+    fn from(value: &str) -> Self {
+        value.parse().unwrap_or_default()
+    }
+}
+impl Display for VerticalAlignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            VerticalAlignment::Top => "Top",
+            VerticalAlignment::Center => "Center",
+            VerticalAlignment::Bottom => "Bottom",
+            VerticalAlignment::Stretch => "Stretch",
+        })
+    }
+}
 
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
 pub struct Padding {
     pub value: f32,
 }
+
+/// Parses a number of pixels, which has to be finite and not negative.
+pub fn parse_pixels(kind: &'static str, value: &str) -> Result<f32, ParseValueError> {
+    value
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|v| v.is_finite() && *v >= 0.0)
+        .ok_or_else(|| ParseValueError::new(kind, value, &[]))
+}
+
+impl FromStr for Padding {
+    type Err = ParseValueError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        parse_pixels("padding", value).map(|value| Padding { value })
+    }
+}
+
+/// The width or height of a node, e.g. `Fill`, `FillPortion(2)` or `120` pixels.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub enum Length {
+    Fill,
+    FillPortion(u16),
+    #[default]
+    Shrink,
+    Fixed(f32),
+}
+impl Length {
+    pub const VALUES: &[&str] = &["Fill", "FillPortion(n)", "Shrink", "a number of pixels"];
+}
+impl FromStr for Length {
+    type Err = ParseValueError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = || ParseValueError::new("length", value, Self::VALUES);
+        let trimmed = value.trim();
+        match trimmed.to_lowercase().as_str() {
+            "fill" => Ok(Length::Fill),
+            "shrink" => Ok(Length::Shrink),
+            lowercase => match lowercase
+                .strip_prefix("fillportion(")
+                .and_then(|portion| portion.strip_suffix(')'))
+            {
+                Some(portion) => portion
+                    .trim()
+                    .parse()
+                    .map(Length::FillPortion)
+                    .map_err(|_| error()),
+                None => parse_pixels("length", trimmed)
+                    .map(Length::Fixed)
+                    .map_err(|_| error()),
+            },
+        }
+    }
+}
+impl Display for Length {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Length::Fill => f.write_str("Fill"),
+            Length::FillPortion(portion) => write!(f, "FillPortion({portion})"),
+            Length::Shrink => f.write_str("Shrink"),
+            Length::Fixed(pixels) => write!(f, "{pixels}"),
+        }
+    }
+}
+impl From<Length> for iced::Length {
+    fn from(length: Length) -> Self {
+        match length {
+            Length::Fill => iced::Length::Fill,
+            Length::FillPortion(portion) => iced::Length::FillPortion(portion),
+            Length::Shrink => iced::Length::Shrink,
+            Length::Fixed(pixels) => iced::Length::Fixed(pixels),
+        }
+    }
+}
+
+/// A color written as `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa` or by name, with components
+/// from 0 to 1.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+impl Color {
+    pub const VALUES: &[&str] = &[
+        "#rgb",
+        "#rgba",
+        "#rrggbb",
+        "#rrggbbaa",
+        "Black",
+        "White",
+        "Transparent",
+    ];
+    pub const BLACK: Color = Color::rgba8(0, 0, 0, 255);
+    pub const WHITE: Color = Color::rgba8(255, 255, 255, 255);
+    pub const TRANSPARENT: Color = Color::rgba8(0, 0, 0, 0);
+
+    pub const fn rgba8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self {
+            r: r as f32 / 255.0,
+            g: g as f32 / 255.0,
+            b: b as f32 / 255.0,
+            a: a as f32 / 255.0,
+        }
+    }
+
+    fn parse_hex(hex: &str) -> Option<Self> {
+        if !hex.is_ascii() {
+            return None;
+        }
+        let digit =
+            |i: usize, len: usize| u8::from_str_radix(hex.get(i * len..(i + 1) * len)?, 16).ok();
+        match hex.len() {
+            3 | 4 => {
+                let short = |i| digit(i, 1).map(|d| d * 17);
+                let a = if hex.len() == 4 { short(3)? } else { 255 };
+                Some(Self::rgba8(short(0)?, short(1)?, short(2)?, a))
+            }
+            6 | 8 => {
+                let a = if hex.len() == 8 { digit(3, 2)? } else { 255 };
+                Some(Self::rgba8(digit(0, 2)?, digit(1, 2)?, digit(2, 2)?, a))
+            }
+            _ => None,
+        }
+    }
+}
+impl FromStr for Color {
+    type Err = ParseValueError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let trimmed = value.trim();
+        let color = match trimmed.strip_prefix('#') {
+            Some(hex) => Self::parse_hex(hex),
+            None => match trimmed.to_lowercase().as_str() {
+                "black" => Some(Color::BLACK),
+                "white" => Some(Color::WHITE),
+                "transparent" => Some(Color::TRANSPARENT),
+                _ => None,
+            },
+        };
+        color.ok_or_else(|| ParseValueError::new("color", value, Self::VALUES))
+    }
+}
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let byte = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        write!(
+            f,
+            "#{:02x}{:02x}{:02x}",
+            byte(self.r),
+            byte(self.g),
+            byte(self.b)
+        )?;
+        if self.a < 1.0 {
+            write!(f, "{:02x}", byte(self.a))?;
+        }
+        Ok(())
+    }
+}
+impl From<Color> for iced::Color {
+    fn from(Color { r, g, b, a }: Color) -> Self {
+        iced::Color::from_rgba(r, g, b, a)
+    }
+}
+
+/// The font family of text, one of the generic families or the name of a loaded font.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Font {
+    #[default]
+    Default,
+    Monospace,
+    Serif,
+    SansSerif,
+    Named(String),
+}
+impl Font {
+    pub const VALUES: &[&str] = &["Default", "Monospace", "Serif", "SansSerif", "a font name"];
+}
+impl FromStr for Font {
+    type Err = ParseValueError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let trimmed = value.trim();
+        match trimmed.to_lowercase().as_str() {
+            "" => Err(ParseValueError::new("font", value, Self::VALUES)),
+            "default" => Ok(Font::Default),
+            "monospace" => Ok(Font::Monospace),
+            "serif" => Ok(Font::Serif),
+            "sansserif" => Ok(Font::SansSerif),
+            _ => Ok(Font::Named(trimmed.to_string())),
+        }
+    }
+}
+impl Display for Font {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Font::Default => "Default",
+            Font::Monospace => "Monospace",
+            Font::Serif => "Serif",
+            Font::SansSerif => "SansSerif",
+            Font::Named(name) => name,
+        })
+    }
+}
+impl From<&Font> for iced::Font {
+    /// Names of fonts are leaked, once per name, as iced only refers to `'static` names.
+    fn from(font: &Font) -> Self {
+        let family = match font {
+            Font::Default => return iced::Font::DEFAULT,
+            Font::Monospace => return iced::Font::MONOSPACE,
+            Font::Serif => iced::font::Family::Serif,
+            Font::SansSerif => iced::font::Family::SansSerif,
+            Font::Named(name) => {
+                static NAMES: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+                let mut names = NAMES.lock().unwrap_or_else(PoisonError::into_inner);
+                let name = match names.iter().find(|known| **known == name) {
+                    Some(known) => known,
+                    None => {
+                        let leaked: &'static str = name.clone().leak();
+                        names.push(leaked);
+                        leaked
+                    }
+                };
+                iced::font::Family::Name(name)
+            }
+        };
+        iced::Font {
+            family,
+            ..iced::Font::DEFAULT
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_alignments() {
+        assert_eq!("center".parse(), Ok(HorizontalAlignment::Center));
+        assert_eq!("Bottom".parse(), Ok(VerticalAlignment::Bottom));
+        assert_eq!(
+            HorizontalAlignment::Stretch.to_string().parse(),
+            Ok(HorizontalAlignment::Stretch)
+        );
+    }
+
+    #[test]
+    fn invalid_alignment_lists_values() {
+        let error = "Middle".parse::<VerticalAlignment>().unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "invalid vertical alignment 'Middle', expected one of: Top, Center, Bottom, Stretch"
+        );
+    }
+
+    #[test]
+    fn can_parse_padding() {
+        assert_eq!(" 20 ".parse(), Ok(Padding { value: 20.0 }));
+        assert_eq!(
+            "-1".parse::<Padding>().unwrap_err().to_string(),
+            "invalid padding '-1'"
+        );
+        assert!("20px".parse::<Padding>().is_err());
+        assert!("NaN".parse::<Padding>().is_err());
+    }
+
+    #[test]
+    fn unknown_alignment_converts_to_default() {
+        assert_eq!(
+            HorizontalAlignment::from("Right"),
+            HorizontalAlignment::Right
+        );
+        assert_eq!(VerticalAlignment::from("Middle"), VerticalAlignment::Top);
+    }
+
+    #[test]
+    fn can_parse_lengths() {
+        assert_eq!("fill".parse(), Ok(Length::Fill));
+        assert_eq!("FillPortion( 3 )".parse(), Ok(Length::FillPortion(3)));
+        assert_eq!(" 120 ".parse(), Ok(Length::Fixed(120.0)));
+        assert_eq!(
+            Length::FillPortion(2).to_string().parse(),
+            Ok(Length::FillPortion(2))
+        );
+        assert_eq!(
+            "-5".parse::<Length>().unwrap_err().to_string(),
+            "invalid length '-5', expected one of: Fill, FillPortion(n), Shrink, a number of pixels"
+        );
+        assert!("FillPortion(-1)".parse::<Length>().is_err());
+    }
+
+    #[test]
+    fn can_parse_colors() {
+        assert_eq!("#fff".parse(), Ok(Color::WHITE));
+        assert_eq!("#00000000".parse(), Ok(Color::TRANSPARENT));
+        assert_eq!("Black".parse(), Ok(Color::BLACK));
+        assert_eq!("#3366ff".parse(), Ok(Color::rgba8(0x33, 0x66, 0xff, 255)));
+        assert_eq!(Color::rgba8(1, 2, 3, 4).to_string(), "#01020304");
+        assert_eq!(
+            "#12345".parse::<Color>().unwrap_err().to_string(),
+            "invalid color '#12345', expected one of: #rgb, #rgba, #rrggbb, #rrggbbaa, Black, White, Transparent"
+        );
+        assert!("#ggg".parse::<Color>().is_err());
+        assert!("#ééé".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn can_parse_fonts() {
+        assert_eq!("monospace".parse(), Ok(Font::Monospace));
+        assert_eq!(
+            " Fira Sans ".parse(),
+            Ok(Font::Named("Fira Sans".to_string()))
+        );
+        assert_eq!(
+            " ".parse::<Font>().unwrap_err().to_string(),
+            "invalid font ' ', expected one of: Default, Monospace, Serif, SansSerif, a font name"
+        );
+    }
+}
@@ -0,0 +1,335 @@
+//! Lookups by name, which let a template be rendered against a model without generating
+//! code for it, e.g. by `iced_xml_runtime` when a template is reloaded.
+
+use std::fmt::Display;
+
+/// A model the runtime interpreter can render a template against.
+///
+/// Every lookup is done by name, so a template can be loaded at runtime
+/// without the model knowing about it at compile time.
+pub trait Reflect {
+    type Message;
+
+    /// Looks up the field `name`, as referenced by `{self.name}`. `None` if there is no such
+    /// field, an error if its value can not be represented.
+    fn field(&self, name: &str) -> Option<Result<Value, Error>>;
+
+    /// Calls the method `name`, as referenced by `{self.name(..)}` or `{name(..)}`. `None` if
+    /// there is no such method, an error if its result can not be represented.
+    ///
+    /// Derived models only answer calls without arguments which their templates make, so a
+    /// reloaded template which calls anything else falls back to the compiled view.
+    fn call(&self, name: &str, args: &[Value]) -> Option<Result<Value, Error>> {
+        let _ = (name, args);
+        None
+    }
+
+    /// Constructs the message variant `name`, as referenced by `Message="{Name(..)}"`.
+    fn message(&self, name: &str, args: &[Value]) -> Option<Self::Message>;
+
+    /// The variant of the model with its fields, as matched by `<Match Value="{self}">`.
+    /// `None` if the model is not an enum.
+    fn variant(&self) -> Option<Variant> {
+        None
+    }
+}
+
+/// A value produced by evaluating a template expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Unit,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    /// A value of an enum, to be matched by the cases of a `Match`.
+    Variant(Variant),
+}
+
+/// A variant of an enum, e.g. `Loaded(State)` or `Editing { draft: String }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub name: String,
+    /// The fields by name, `0`, `1`, .. for tuple variants. `None` for fields which don't
+    /// convert into a [`Value`].
+    pub fields: Vec<(String, Option<Value>)>,
+}
+
+impl Variant {
+    /// A variant without fields.
+    pub fn unit(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Whether the fields are named `0`, `1`, .., like those of a tuple variant.
+    pub fn is_tuple(&self) -> bool {
+        self.fields
+            .iter()
+            .enumerate()
+            .all(|(i, (name, _))| *name == i.to_string())
+    }
+
+    pub fn field(&self, name: &str) -> Option<&Option<Value>> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+    }
+}
+
+impl Value {
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Value::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Int(i) => Some(*i as f64),
+            Value::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Unit => Ok(()),
+            Value::Bool(b) => b.fmt(f),
+            Value::Int(i) => i.fmt(f),
+            Value::Float(x) => x.fmt(f),
+            Value::String(s) => s.fmt(f),
+            Value::Variant(variant) => variant.name.fmt(f),
+        }
+    }
+}
+
+impl From<Variant> for Value {
+    fn from(value: Variant) -> Self {
+        Value::Variant(value)
+    }
+}
+
+impl From<()> for Value {
+    fn from(_: ()) -> Self {
+        Value::Unit
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($ty:ty),*) => {
+        $(impl From<$ty> for Value {
+            fn from(value: $ty) -> Self {
+                Value::Int(value as i64)
+            }
+        })*
+    };
+}
+impl_from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+macro_rules! impl_try_from_int {
+    ($($ty:ty),*) => {
+        $(impl TryFrom<$ty> for Value {
+            type Error = Error;
+
+            fn try_from(value: $ty) -> Result<Self, Self::Error> {
+                i64::try_from(value)
+                    .map(Value::Int)
+                    .map_err(|_| Error::IntOutOfRange(value.to_string()))
+            }
+        })*
+    };
+}
+impl_try_from_int!(u64, usize, isize, i128, u128);
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::Float(value as f64)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Float(value)
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<char> for Value {
+    fn from(value: char) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+macro_rules! impl_int_try_from_value {
+    ($($ty:ty),*) => {
+        $(impl TryFrom<Value> for $ty {
+            type Error = Error;
+
+            fn try_from(value: Value) -> Result<Self, Self::Error> {
+                let int = value.as_int().ok_or_else(|| Error::mismatched(stringify!($ty), &value))?;
+                <$ty>::try_from(int).map_err(|_| Error::mismatched(stringify!($ty), &value))
+            }
+        })*
+    };
+}
+impl_int_try_from_value!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+impl TryFrom<Value> for bool {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        value
+            .as_bool()
+            .ok_or_else(|| Error::mismatched("bool", &value))
+    }
+}
+
+impl TryFrom<Value> for f32 {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        value
+            .as_float()
+            .map(|f| f as f32)
+            .ok_or_else(|| Error::mismatched("f32", &value))
+    }
+}
+
+impl TryFrom<Value> for f64 {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        value
+            .as_float()
+            .ok_or_else(|| Error::mismatched("f64", &value))
+    }
+}
+
+impl TryFrom<Value> for String {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        match value {
+            Value::String(s) => Ok(s),
+            value => Err(Error::mismatched("String", &value)),
+        }
+    }
+}
+
+/// Why a template can not be rendered against a reflected model.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// The expression is not part of the subset the interpreter understands.
+    InvalidExpr { expr: String, reason: String },
+    /// `{self.name}` references a field the model does not expose.
+    UnknownField(String),
+    /// `{self.name}` references a field of a generic type, which can not be reflected.
+    GenericField(String),
+    /// `{name(..)}` references a method the model does not expose.
+    UnknownMethod(String),
+    /// `Message="{Name}"` references a variant the model cannot construct.
+    UnknownMessage(String),
+    /// An integer does not fit into a [`Value::Int`](Value::Int).
+    IntOutOfRange(String),
+    /// A value can not be converted into the type an argument expects.
+    MismatchedType {
+        expected: &'static str,
+        value: String,
+    },
+    /// A pattern is not part of the subset the interpreter understands.
+    InvalidPattern { pattern: String, reason: String },
+    /// None of the cases of a `Match` match its value.
+    NoMatchingCase(String),
+    /// A value is used, which does not convert into a [`Value`].
+    Unreflected(String),
+    /// The node can not be rendered by the interpreter yet.
+    UnsupportedNode(&'static str),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::InvalidExpr { expr, reason } => {
+                write!(f, "invalid expression '{expr}': {reason}")
+            }
+            Error::UnknownField(name) => write!(f, "model has no field '{name}'"),
+            Error::GenericField(name) => {
+                write!(
+                    f,
+                    "field '{name}' has a generic type, which can not be reloaded"
+                )
+            }
+            Error::UnknownMethod(name) => write!(f, "model has no method '{name}'"),
+            Error::UnknownMessage(name) => write!(f, "message has no variant '{name}'"),
+            Error::IntOutOfRange(value) => write!(f, "integer {value} does not fit into an i64"),
+            Error::MismatchedType { expected, value } => {
+                write!(f, "expected {expected}, found '{value}'")
+            }
+            Error::InvalidPattern { pattern, reason } => {
+                write!(f, "invalid pattern '{pattern}': {reason}")
+            }
+            Error::NoMatchingCase(value) => write!(f, "no MatchCase matches '{value}'"),
+            Error::Unreflected(value) => {
+                write!(f, "'{value}' has a type which can not be reloaded")
+            }
+            Error::UnsupportedNode(name) => write!(f, "'{name}' nodes are not supported"),
+        }
+    }
+}
+
+impl Error {
+    pub(crate) fn mismatched(expected: &'static str, value: &Value) -> Self {
+        Error::MismatchedType {
+            expected,
+            value: format!("{value:?}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::convert::Infallible> for Error {
+    fn from(value: std::convert::Infallible) -> Self {
+        match value {}
+    }
+}
@@ -0,0 +1,89 @@
+//! The hook `#[derive(IcedComponent)]` renders reloaded templates through.
+//!
+//! Generated views only refer to this crate, so deriving crates don't need to depend on
+//! `iced_xml_runtime` themselves. Until an app installs a [`Reload`], e.g. with
+//! `iced_xml_runtime::hot_reload::install()`, [`view`] returns `None` and the compiled
+//! view is used.
+
+use std::{any::Any, rc::Rc, sync::OnceLock};
+
+use iced::Element;
+
+use crate::reflect::{Error, Reflect, Value, Variant};
+
+/// A message of the model being rendered, with its type erased so a [`Reload`] can be
+/// shared by all models.
+#[derive(Clone)]
+pub struct AnyMessage(Rc<dyn Any>);
+
+impl AnyMessage {
+    fn downcast<M: Clone + 'static>(self) -> Option<M> {
+        self.0.downcast_ref::<M>().cloned()
+    }
+}
+
+impl std::fmt::Debug for AnyMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AnyMessage")
+    }
+}
+
+/// Renders the template at a path against a model, if it changed since it was compiled.
+pub trait Reload: Send + Sync {
+    fn view<'a>(
+        &self,
+        path: &str,
+        lenient: bool,
+        model: &dyn Reflect<Message = AnyMessage>,
+    ) -> Option<Element<'a, AnyMessage>>;
+}
+
+static RELOAD: OnceLock<Box<dyn Reload>> = OnceLock::new();
+
+/// Installs the [`Reload`] used by generated views. Only the first one installed is used.
+pub fn install(reload: impl Reload + 'static) {
+    let _ = RELOAD.set(Box::new(reload));
+}
+
+/// Renders the template at `path` through the installed [`Reload`], if there is one.
+pub fn view<'a, M>(path: &str, lenient: bool, model: &M) -> Option<Element<'a, M::Message>>
+where
+    M: Reflect,
+    M::Message: Clone + 'static,
+{
+    let view = RELOAD.get()?.view(path, lenient, &Erased(model))?;
+    Some(view.map(|message| {
+        message
+            .downcast()
+            .expect("messages are constructed by the model being rendered")
+    }))
+}
+
+/// A model whose messages are erased into [`AnyMessage`]s.
+struct Erased<'m, M>(&'m M);
+
+impl<M> Reflect for Erased<'_, M>
+where
+    M: Reflect,
+    M::Message: 'static,
+{
+    type Message = AnyMessage;
+
+    fn field(&self, name: &str) -> Option<Result<Value, Error>> {
+        self.0.field(name)
+    }
+
+    fn call(&self, name: &str, args: &[Value]) -> Option<Result<Value, Error>> {
+        self.0.call(name, args)
+    }
+
+    fn message(&self, name: &str, args: &[Value]) -> Option<Self::Message> {
+        self.0
+            .message(name, args)
+            .map(|message| AnyMessage(Rc::new(message)))
+    }
+
+    fn variant(&self) -> Option<Variant> {
+        self.0.variant()
+    }
+}
@@ -0,0 +1,50 @@
+use std::ops::Range;
+
+/// Identifies the template a span points into, for callers parsing several templates.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId(pub u32);
+
+/// A 1-based line and column, counted in characters.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LineColumn {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// A range of a template's source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub file: FileId,
+    /// In bytes
+    pub range: Range<usize>,
+    pub start: LineColumn,
+    pub end: LineColumn,
+}
+
+/// An attribute as written in a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeSpan {
+    pub name: String,
+    /// From the name to the closing quote
+    pub span: Span,
+    /// The value without quotes
+    pub value: Span,
+}
+
+/// Where a node was written in its template, empty for nodes built in code.
+///
+/// Nodes written at different places are not equal; strip their spans to compare the trees.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Spans {
+    /// The whole element
+    pub node: Option<Span>,
+    pub attributes: Vec<AttributeSpan>,
+    /// The text content of the element, if any
+    pub text: Option<Span>,
+}
+
+impl Spans {
+    pub fn attribute(&self, name: &str) -> Option<&AttributeSpan> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+}
@@ -0,0 +1,186 @@
+use iced_xml_core::{ComponentNode, MatchCaseNode, Node};
+use syn::{DataEnum, Expr, Fields, Stmt};
+
+use iced_xml_codegen::{is_iced_expr, parse_pattern, trim_iced_expr};
+
+use crate::diagnostic::TemplateError;
+
+type Result<T> = std::result::Result<T, TemplateError>;
+
+/// Turns the cases of every `<Match Value="{self}">`, or `{(self)}` and the like, into
+/// patterns over the variants of the deriving enum, e.g. `Case="Loaded"` into
+/// `Self::Loaded(_0)`.
+///
+/// Without a `Destructure` attribute, tuple variants bind their fields as `_0`, `_1`, ..
+/// and struct variants bind their fields by name. With one, it is used as the pattern for
+/// the fields, e.g. `Destructure="{State { tasks, .. }}"` for `Loaded(State)`.
+pub(crate) fn destructure_self_matches(
+    node: &mut ComponentNode,
+    name: &syn::Ident,
+    data: &DataEnum,
+) -> Result<()> {
+    match &mut node.node {
+        Node::Button(_) | Node::Text(_) | Node::Placeholder(_) => Ok(()),
+        Node::Column(column_node) => column_node
+            .content
+            .iter_mut()
+            .try_for_each(|c| destructure_self_matches(c, name, data)),
+        Node::Row(row_node) => row_node
+            .content
+            .iter_mut()
+            .try_for_each(|c| destructure_self_matches(c, name, data)),
+        Node::Match(match_node) => {
+            if is_self(&match_node.value) {
+                for case in match_node.cases.iter_mut() {
+                    destructure_case(case, name, data).map_err(|e| e.with_spans(&case.spans))?;
+                }
+                check_exhaustive(&match_node.value, &match_node.cases, data)
+                    .map_err(|e| e.with_spans(&node.spans))?;
+            }
+
+            match_node
+                .cases
+                .iter_mut()
+                .filter_map(|case| case.content.as_deref_mut())
+                .try_for_each(|c| destructure_self_matches(c, name, data))
+        }
+    }
+}
+
+/// Whether the value of a `Match` is `self`, possibly in parentheses, braces or borrowed.
+fn is_self(value: &str) -> bool {
+    let value = value.trim();
+    if !is_iced_expr(value) {
+        return false;
+    }
+    let Ok(mut expr) = syn::parse_str::<Expr>(trim_iced_expr(value)) else {
+        return false;
+    };
+    loop {
+        expr = match expr {
+            Expr::Paren(paren) => *paren.expr,
+            Expr::Group(group) => *group.expr,
+            Expr::Reference(reference) => *reference.expr,
+            Expr::Block(block) if block.block.stmts.len() == 1 => {
+                match block.block.stmts.into_iter().next() {
+                    Some(Stmt::Expr(expr, None)) => expr,
+                    _ => return false,
+                }
+            }
+            Expr::Path(path) => return path.qself.is_none() && path.path.is_ident("self"),
+            _ => return false,
+        }
+    }
+}
+
+fn destructure_case(case: &mut MatchCaseNode, name: &syn::Ident, data: &DataEnum) -> Result<()> {
+    if case.case.trim() == "_" {
+        return Ok(());
+    }
+
+    let variant_name = case.case.rsplit("::").next().unwrap_or_default().trim();
+    let Some(variant) = data.variants.iter().find(|v| v.ident == variant_name) else {
+        return Err(TemplateError::new(
+            "Case",
+            &case.case,
+            format!(
+                "'{}' is not a variant of '{name}', expected one of: {}",
+                case.case,
+                variant_names(data)
+            ),
+        ));
+    };
+
+    let user_destructure = case.destructure.as_deref().map(trim_iced_expr);
+    let destructure = match (&variant.fields, user_destructure) {
+        (Fields::Unit, None) => None,
+        (Fields::Unit, Some(_)) => {
+            return Err(TemplateError::new(
+                "Destructure",
+                case.destructure.as_deref().unwrap_or_default(),
+                format!("'{}' has no fields to destructure", case.case),
+            ));
+        }
+        (Fields::Unnamed(_), Some(fields)) => Some(format!("({fields})")),
+        (Fields::Unnamed(fields), None) => Some(format!(
+            "({})",
+            (0..fields.unnamed.len())
+                .map(|i| format!("_{i}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        (Fields::Named(_), Some(fields)) => Some(format!("{{ {fields} }}")),
+        (Fields::Named(fields), None) => Some(format!(
+            "{{ {} }}",
+            fields
+                .named
+                .iter()
+                .filter_map(|f| f.ident.as_ref().map(|i| i.to_string()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    };
+
+    let written = case.destructure.take().unwrap_or_default();
+    case.case = format!("Self::{}", variant.ident);
+    // Keep it an iced expr, so struct patterns don't lose their braces when trimmed
+    case.destructure = destructure.map(|d| format!("{{{d}}}"));
+
+    // Errors have to refer to the pattern as written in the template
+    parse_pattern(&case.case, case.destructure.as_deref()).map_err(|e| TemplateError {
+        value: written,
+        ..e
+    })?;
+
+    Ok(())
+}
+
+fn check_exhaustive(value: &str, cases: &[MatchCaseNode], data: &DataEnum) -> Result<()> {
+    if cases.iter().any(|case| case.case.trim() == "_") {
+        return Ok(());
+    }
+
+    let missing = data
+        .variants
+        .iter()
+        .map(|v| format!("Self::{}", v.ident))
+        .filter(|v| !cases.iter().any(|case| &case.case == v))
+        .collect::<Vec<_>>();
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(TemplateError::new(
+            "Value",
+            value,
+            format!(
+                "Match on self is missing a MatchCase for: {}",
+                missing.join(", ")
+            ),
+        ))
+    }
+}
+
+fn variant_names(data: &DataEnum) -> String {
+    data.variants
+        .iter()
+        .map(|v| v.ident.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognises_self_values() {
+        assert!(is_self("{self}"));
+        assert!(is_self("{ self }"));
+        assert!(is_self("{(self)}"));
+        assert!(is_self("{&self}"));
+        assert!(!is_self("{self.state}"));
+        assert!(!is_self("{Self}"));
+        assert!(!is_self("self"));
+    }
+}
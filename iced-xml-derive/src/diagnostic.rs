@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+use iced_xml_parse::{ParseOptions, Parsed};
+use proc_macro2::Span;
+
+pub(crate) use iced_xml_codegen::TemplateError;
+
+/// The source of a template, used to point errors at the offending line.
+pub(crate) struct Template {
+    template: iced_xml_codegen::Template,
+    /// The span errors are reported on, i.e. the `#[source]` attribute
+    pub(crate) span: Span,
+}
+
+impl Template {
+    pub(crate) fn read(path: PathBuf, span: Span) -> syn::Result<Self> {
+        let template = iced_xml_codegen::Template::read(&path).map_err(|e| {
+            syn::Error::new(
+                span,
+                format!("could not read component file '{}': {e}", path.display()),
+            )
+        })?;
+
+        Ok(Self {
+            template: template.with_display_path(display_path),
+            span,
+        })
+    }
+
+    /// A template given as a string literal, with errors reported on the literal.
+    pub(crate) fn inline(source: String, span: Span) -> Self {
+        Self {
+            template: iced_xml_codegen::Template::inline(source),
+            span,
+        }
+    }
+
+    /// `None` for templates written inline in rust code.
+    pub(crate) fn path(&self) -> Option<&Path> {
+        self.template.path.as_deref()
+    }
+
+    /// Includes are resolved relative to the template file, and remembered to show errors in
+    /// them.
+    pub(crate) fn parse(&mut self, options: &ParseOptions) -> syn::Result<Parsed> {
+        self.template
+            .parse(options)
+            .map_err(|message| syn::Error::new(self.span, message))
+    }
+
+    /// Describes an error or warning of the parser, pointing at its position.
+    pub(crate) fn describe_parse_error(&self, error: &iced_xml_parse::Error) -> String {
+        self.template.describe_parse_error(error)
+    }
+
+    pub(crate) fn error(&self, error: TemplateError) -> syn::Error {
+        syn::Error::new(self.span, self.template.describe_error(&error))
+    }
+}
+
+/// Shortens the path to be relative to the crate, like rustc does for its own errors.
+fn display_path(path: &Path) -> String {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let path = path.canonicalize().unwrap_or(path.to_path_buf());
+    let manifest_dir = Path::new(&manifest_dir)
+        .canonicalize()
+        .unwrap_or(manifest_dir.into());
+
+    path.strip_prefix(manifest_dir)
+        .unwrap_or(&path)
+        .display()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(path: PathBuf, source: &str) -> Template {
+        let mut template = Template::inline(source.to_string(), Span::call_site());
+        template.template.path = Some(path);
+        template
+    }
+
+    #[test]
+    fn error_shows_snippet() {
+        let mut template = template(
+            PathBuf::from("src/test.ixml"),
+            "<Component>\n  <Text Content=\"{self.}\" />\n</Component>",
+        );
+        let parsed = template.parse(&ParseOptions::strict()).unwrap();
+
+        let error =
+            template.error(iced_xml_codegen::view_component(&parsed.component).unwrap_err());
+
+        assert_eq!(
+            error.to_string(),
+            "in attribute 'Content': invalid expression '{self.}': unexpected end of input, expected identifier or integer
+ --> src/test.ixml:2:9
+  |
+2 |   <Text Content=\"{self.}\" />
+  |         ^^^^^^^^^^^^^^^^^"
+        );
+    }
+
+    #[test]
+    fn error_in_include_shows_included_template() {
+        let dir =
+            std::env::temp_dir().join(format!("iced-xml-derive-include-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("header.ixml"),
+            "<Component>\n  <Text>{self.}</Text>\n</Component>",
+        )
+        .unwrap();
+        let mut template = template(
+            dir.join("main.ixml"),
+            "<Component><Include Source=\"header.ixml\" /></Component>",
+        );
+        let parsed = template.parse(&ParseOptions::strict()).unwrap();
+
+        let error = template
+            .error(iced_xml_codegen::view_component(&parsed.component).unwrap_err())
+            .to_string();
+
+        assert!(error.contains("header.ixml:2:9"), "{error}");
+        assert!(error.contains("2 |   <Text>{self.}</Text>"), "{error}");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
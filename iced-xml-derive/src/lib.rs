@@ -1,60 +1,300 @@
-use std::{any::Any, ops::Deref};
-mod view;
+mod destructure;
+mod diagnostic;
+mod messages;
+mod props;
+#[cfg(feature = "hot-reload")]
+mod reflect;
+mod resolve;
+mod validate;
 
 use proc_macro::TokenStream;
-use proc_macro_error::abort;
-use quote::quote;
-use syn::{DataStruct, DeriveInput, Fields, Result, parse_macro_input, spanned::Spanned};
+use quote::{quote, quote_spanned};
+use syn::{DeriveInput, Result, parse_macro_input, spanned::Spanned};
 
 fn derive_component_impl(input: DeriveInput) -> Result<proc_macro2::TokenStream> {
-    let source_attr = input
-        .attrs
-        .iter()
-        .filter(|attr| {
-            attr.path()
-                .get_ident()
-                .is_some_and(|path| path.to_string() == "source".to_string())
-        })
-        .next();
-    if source_attr.is_none() {
-        return Err(syn::Error::new(input.span(), "Missing 'source' attriute"));
+    let options = parse_ixml_options(&input.attrs)?;
+    let (templates, track_env) = parse_source_attribute(find_source_attribute(&input)?, &options)?;
+    let component = derive_component_from(input, &options, templates)?;
+    Ok(quote!(
+        #component
+        #track_env
+    ))
+}
+
+/// A template and the method rendering it, e.g. `view_compact` for `compact = ".."`.
+struct View {
+    name: syn::Ident,
+    template: diagnostic::Template,
+    component: iced_xml_core::Component,
+    /// Problems skipped by a lenient parse, described like errors
+    warnings: Vec<String>,
+    /// The templates included or imported by this one
+    includes: Vec<std::path::PathBuf>,
+}
+
+impl View {
+    /// `view` implements `IcedComponent`, all other views are inherent methods.
+    fn is_main(&self) -> bool {
+        self.name == "view"
     }
 
-    let component = parse_template_attribute(source_attr.unwrap())?;
+    fn method(&self) -> syn::Ident {
+        quote::format_ident!("view_{}", self.name)
+    }
+}
 
-    match input.data {
+fn derive_component_from(
+    input: DeriveInput,
+    options: &IxmlOptions,
+    templates: Vec<(syn::Ident, diagnostic::Template)>,
+) -> Result<proc_macro2::TokenStream> {
+    let mut views = templates
+        .into_iter()
+        .map(|(name, mut template)| {
+            let parsed = template.parse(&options.parse_options())?;
+            let warnings = parsed
+                .warnings
+                .iter()
+                .map(|w| template.describe_parse_error(w))
+                .collect();
+            Ok(View {
+                name,
+                template,
+                component: parsed.component,
+                warnings,
+                includes: parsed.files.into_iter().map(|(_, path)| path).collect(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let types = parse_view_types(options, &views)?;
+
+    match &input.data {
         syn::Data::Struct(data_struct) => {
-            Ok(derive_component_struct(input.ident, data_struct, component))
+            let fields = match &data_struct.fields {
+                syn::Fields::Named(fields) => fields
+                    .named
+                    .iter()
+                    .filter_map(|f| f.ident.clone())
+                    .collect(),
+                syn::Fields::Unnamed(_) | syn::Fields::Unit => Vec::new(),
+            };
+            for view in &views {
+                if let Some(content) = &view.component.content {
+                    validate::check_fields(content, &input.ident, &fields)
+                        .map_err(|e| view.template.error(e))?;
+                }
+            }
+            derive_component_views(&input, &types, &fields, &views)
+        }
+        syn::Data::Enum(data_enum) => {
+            for view in views.iter_mut() {
+                if let Some(content) = view.component.content.as_mut() {
+                    destructure::destructure_self_matches(content, &input.ident, data_enum)
+                        .map_err(|e| view.template.error(e))?;
+                }
+            }
+            // Fields depend on the variant, so none of them can be reflected
+            derive_component_views(&input, &types, &[], &views)
         }
-        syn::Data::Enum(data_enum) => todo!(),
-        syn::Data::Union(data_union) => todo!(),
+        syn::Data::Union(data_union) => Err(syn::Error::new(
+            data_union.union_token.span,
+            "IcedComponent can not be derived for unions",
+        )),
     }
 }
 
-fn derive_component_struct(
-    name: syn::Ident,
-    data: DataStruct,
-    component: iced_xml_core::Component,
-) -> proc_macro2::TokenStream {
-    let message = match component.message_type {
-        Some(ty) => ty,
-        None => "()".to_string(),
+/// Resolves the types of the main view, which all other views have to share.
+fn parse_view_types(options: &IxmlOptions, views: &[View]) -> Result<ComponentTypes> {
+    let main = views.iter().find(|view| view.is_main()).unwrap();
+    let types = parse_component_types(options, &main.template, &main.component)?;
+
+    for view in views.iter().filter(|view| !view.is_main()) {
+        let view_types = parse_component_types(options, &view.template, &view.component)?;
+        let (message, view_message) = (&types.message, &view_types.message);
+        if view.component.message_type.is_some()
+            && quote!(#message).to_string() != quote!(#view_message).to_string()
+        {
+            let written = view.component.message_type.as_deref().unwrap_or_default();
+            return Err(view.template.error(
+                diagnostic::TemplateError::new(
+                    "Message",
+                    written,
+                    format!(
+                        "all views share the message type '{}', but this is '{written}'",
+                        quote!(#message)
+                    ),
+                )
+                .with_spans(&view.component.spans),
+            ));
+        }
+    }
+
+    Ok(types)
+}
+
+#[cfg_attr(not(feature = "hot-reload"), allow(unused_variables))]
+fn derive_component_views(
+    input: &DeriveInput,
+    types: &ComponentTypes,
+    fields: &[syn::Ident],
+    views: &[View],
+) -> Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let message = &types.message;
+    let message = quote!(#message);
+    let message_check = check_messages(types, views);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut main_view = None;
+    let mut methods = Vec::new();
+    let mut track_templates = Vec::new();
+    let mut warnings = Vec::new();
+    for view in views {
+        // Proc macros can not emit warnings, but deprecation warnings carry a custom note
+        warnings.extend(view.warnings.iter().map(|warning| {
+            quote_spanned!(view.template.span=>
+                const _: () = {
+                    #[deprecated(note = #warning)]
+                    struct TemplateWarning;
+                    let _ = TemplateWarning;
+                };
+            )
+        }));
+        let (body, track_template) = derive_view_body(types, &input.generics, view)?;
+        track_templates.extend(track_template);
+        if view.is_main() {
+            main_view = Some(body);
+        } else {
+            let method = view.method();
+            // `Self::Message` is ambiguous outside of the trait impl
+            let body = iced_xml_codegen::qualify_self_message(body);
+            methods.push(quote!(
+                #vis fn #method(&self) -> ::iced::Element<'_, #message> {
+                    #body
+                }
+            ));
+        }
+    }
+    let inherent_views = (!methods.is_empty()).then(|| {
+        quote!(
+            impl #impl_generics #name #ty_generics #where_clause {
+                #(#methods)*
+            }
+        )
+    });
+
+    #[cfg(feature = "hot-reload")]
+    let reflect = {
+        // Only templates in files can be reloaded
+        let contents = views
+            .iter()
+            .filter(|view| view.template.path().is_some())
+            .filter_map(|view| view.component.content.as_ref())
+            .collect::<Vec<_>>();
+        (!contents.is_empty()).then(|| {
+            let reflect = reflect::reflect_component(input, &message, fields, &contents);
+            quote!(#[cfg(debug_assertions)] #reflect)
+        })
     };
-    let message = syn::parse_str::<syn::Path>(&message).unwrap();
-    let view = view::view_component_node(&component.content.unwrap());
+    #[cfg(not(feature = "hot-reload"))]
+    let reflect = quote!();
 
-    quote!(
-    impl ::iced_xml_core::IcedComponent for #name {
-        type Message = crate::#message;
+    Ok(quote!(
+    impl #impl_generics ::iced_xml_core::IcedComponent for #name #ty_generics #where_clause {
+        type Message = #message;
         fn view(&self) -> ::iced::Element<'_, Self::Message> {
-            #view
-            .into()
+            #message_check
+            #main_view
         }
     }
+
+    #inherent_views
+    #reflect
+    #(#warnings)*
+    #(#track_templates)*
+        ))
+}
+
+/// Generates the body of a view method, along with tokens tracking its template file.
+fn derive_view_body(
+    types: &ComponentTypes,
+    generics: &syn::Generics,
+    view: &View,
+) -> Result<(proc_macro2::TokenStream, Option<proc_macro2::TokenStream>)> {
+    let template = &view.template;
+    // Fails to compile if the template was written for another model
+    let model_check = types.model.as_ref().map(|model| {
+        let model = with_generics(model, generics);
+        quote_spanned!(types.span=>
+            let _: &#model = self;
+        )
+    });
+    let content =
+        iced_xml_codegen::view_component(&view.component).map_err(|e| template.error(e))?;
+    let path = template
+        .path()
+        .map(|path| path.to_string_lossy().into_owned());
+
+    #[cfg(feature = "hot-reload")]
+    let hot_reload = path.as_ref().map(|path| {
+        let lenient = types.lenient;
+        quote!(
+            #[cfg(debug_assertions)]
+            if let ::std::option::Option::Some(view) =
+                ::iced_xml_core::reload::view(#path, #lenient, self)
+            {
+                return view;
+            }
         )
+    });
+    #[cfg(not(feature = "hot-reload"))]
+    let hot_reload = quote!();
+    // The template is read by the macro, so cargo needs to be told to rebuild when it or one of
+    // its includes changes
+    let track_template = path.map(|path| {
+        let includes = view
+            .includes
+            .iter()
+            .map(|include| include.to_string_lossy().into_owned());
+        quote!(
+            const _: &[u8] = include_bytes!(#path);
+            #(const _: &[u8] = include_bytes!(#includes);)*
+        )
+    });
+
+    Ok((
+        quote!(
+            #model_check
+            #hot_reload
+            #content
+            .into()
+        ),
+        track_template,
+    ))
 }
 
-#[proc_macro_derive(IcedComponent, attributes(source))]
+/// Adds the generics of the deriving type to a model named without them, e.g. `Model="Editor"`
+/// for `Editor<T>`, which would otherwise be missing its generic arguments.
+fn with_generics(model: &syn::Type, generics: &syn::Generics) -> syn::Type {
+    let syn::Type::Path(path) = model else {
+        return model.clone();
+    };
+    let named_without_generics = path.qself.is_none()
+        && path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.arguments.is_none());
+    if named_without_generics && !generics.params.is_empty() {
+        let (_, ty_generics, _) = generics.split_for_impl();
+        syn::parse_quote!(#path #ty_generics)
+    } else {
+        model.clone()
+    }
+}
+
+#[proc_macro_derive(IcedComponent, attributes(source, ixml))]
 pub fn derive_component(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     match derive_component_impl(input) {
@@ -70,73 +310,390 @@ pub fn derive_component_test(input: proc_macro2::TokenStream) -> Result<proc_mac
     derive_component_impl(item)
 }
 
-fn parse_template_attribute(attr: &syn::Attribute) -> Result<iced_xml_core::Component> {
-    // This would work in rust 1.88+ only
-    // if let syn::Meta::NameValue(path) = attr.meta
-    //     && let syn::Expr::Lit(path) = path.value
-    //     && let syn::Lit::Str(path) = path.lit
-    // {
-    //     Some(iced_xml_parse::parse_file(path.value()))
-    // } else {
-    //     None
-    // }
-    //
-
-    // Unstable, so can not use span to get source file :(
-    // let path_span = proc_macro::Span::call_site();
-    // let source_file = path_span.type_id()
-
-    let toml_dir =
-        std::path::Path::new(std::env::var("CARGO_MANIFEST_DIR").unwrap().as_str()).join("src");
-
-    let error = syn::Error::new(attr.span(), "Invalid syntax");
-
-    if let syn::Meta::NameValue(path) = attr.meta.clone() {
-        if let syn::Expr::Lit(path) = path.value {
-            if let syn::Lit::Str(path) = path.lit {
-                let path = toml_dir.join(path.value());
-                if let Ok(exists) = std::fs::exists(&path) {
-                    if !exists {
-                        Err(syn::Error::new(
-                            attr.span(),
-                            format!("could not find component file '{path:?}'"),
-                        ))
-                    } else {
-                        Ok(iced_xml_parse::parse_file(path))
-                    }
-                } else {
-                    Err(syn::Error::new(
-                        attr.span(),
-                        format!("could not find component file '{path:?}'"),
-                    ))
-                }
+/// The options given with `#[ixml(..)]`.
+#[derive(Default)]
+struct IxmlOptions {
+    message: Option<syn::Type>,
+    model: Option<syn::Type>,
+    /// Directory to look up templates in, relative to the crate
+    root: Option<syn::LitStr>,
+    /// Fail to compile if a message variant is never sent by a template
+    deny_unused_messages: bool,
+    /// Parse templates leniently, warning about what the parser does not understand
+    lenient: bool,
+    span: Option<proc_macro2::Span>,
+}
+
+impl IxmlOptions {
+    fn parse_options(&self) -> iced_xml_parse::ParseOptions {
+        if self.lenient {
+            iced_xml_parse::ParseOptions::lenient()
+        } else {
+            iced_xml_parse::ParseOptions::strict()
+        }
+    }
+}
+
+fn parse_ixml_options(attrs: &[syn::Attribute]) -> Result<IxmlOptions> {
+    let mut options = IxmlOptions::default();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("ixml")) {
+        options.span = Some(attr.span());
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("message") {
+                options.message = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("model") {
+                options.model = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("root") {
+                options.root = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("deny_unused_messages") {
+                options.deny_unused_messages = true;
+                Ok(())
+            } else if meta.path.is_ident("lenient") {
+                options.lenient = true;
+                Ok(())
             } else {
-                Err(error)
+                Err(meta.error(
+                    "expected `message`, `model`, `root`, `lenient` or `deny_unused_messages`",
+                ))
+            }
+        })?;
+    }
+
+    Ok(options)
+}
+
+/// The rust types a component is generated for.
+struct ComponentTypes {
+    message: syn::Type,
+    model: Option<syn::Type>,
+    span: proc_macro2::Span,
+    /// Fail to compile if a message variant is never sent by a template
+    deny_unused_messages: bool,
+    /// Templates are parsed leniently, also when they are reloaded
+    #[cfg_attr(not(feature = "hot-reload"), allow(dead_code))]
+    lenient: bool,
+}
+
+/// Resolves the `Message` and `Model` types, preferring `#[ixml(message = .., model = ..)]`
+/// over the attributes of the template's root.
+fn parse_component_types(
+    options: &IxmlOptions,
+    template: &diagnostic::Template,
+    component: &iced_xml_core::Component,
+) -> Result<ComponentTypes> {
+    let parse_template_type = |name: &'static str, ty: &Option<String>| {
+        ty.as_deref()
+            .map(|ty| {
+                syn::parse_str::<syn::Type>(ty).map_err(|e| {
+                    template.error(
+                        diagnostic::TemplateError::new(
+                            name,
+                            ty,
+                            format!("invalid type '{ty}': {e}"),
+                        )
+                        .with_spans(&component.spans),
+                    )
+                })
+            })
+            .transpose()
+    };
+    let message = match &options.message {
+        Some(message) => Some(message.clone()),
+        None => parse_template_type("Message", &component.message_type)?,
+    };
+    let model = match &options.model {
+        Some(model) => Some(model.clone()),
+        None => parse_template_type("Model", &component.model_type)?,
+    };
+
+    Ok(ComponentTypes {
+        message: message.unwrap_or_else(|| syn::parse_quote!(())),
+        model,
+        span: options.span.unwrap_or(template.span),
+        deny_unused_messages: options.deny_unused_messages,
+        lenient: options.lenient,
+    })
+}
+
+/// Checks the messages sent by the templates against the message enum, by matching on their
+/// variants. With `#[ixml(deny_unused_messages)]` the match is exhaustive, so rustc lists the
+/// variants no template sends.
+fn check_messages(types: &ComponentTypes, views: &[View]) -> Option<proc_macro2::TokenStream> {
+    let contents = views
+        .iter()
+        .filter_map(|view| view.component.content.as_ref())
+        .collect::<Vec<_>>();
+    message_check(
+        &types.message,
+        &contents,
+        types.deny_unused_messages,
+        types.span,
+    )
+}
+
+/// Matches on the variants of `message` sent by the contents, which fails to compile if one of
+/// them is not a variant. Without a path to the message enum, rustc only checks the messages
+/// where they are constructed.
+fn message_check(
+    message: &syn::Type,
+    contents: &[&iced_xml_core::ComponentNode],
+    exhaustive: bool,
+    span: proc_macro2::Span,
+) -> Option<proc_macro2::TokenStream> {
+    let syn::Type::Path(path) = message else {
+        return None;
+    };
+    // Generic arguments are inferred, they are not allowed in patterns
+    let mut path = path.path.clone();
+    path.segments.last_mut()?.arguments = syn::PathArguments::None;
+
+    let mut variants = Vec::new();
+    for content in contents {
+        for variant in messages::message_variants(content) {
+            if !variants.contains(&variant) {
+                variants.push(variant);
             }
-        } else {
-            Err(error)
         }
-    } else {
-        Err(error)
     }
+    let wildcard = (!exhaustive).then(|| quote!(_ => {}));
+    if variants.is_empty() && wildcard.is_some() {
+        return None;
+    }
+
+    Some(quote_spanned!(span=>
+        let _ = |message: &#message| match message {
+            #(#path::#variants { .. } => {})*
+            #wildcard
+        };
+    ))
+}
+
+fn find_source_attribute(input: &DeriveInput) -> Result<&syn::Attribute> {
+    input
+        .attrs
+        .iter()
+        .find(|attr| attr.path().get_ident().is_some_and(|path| path == "source"))
+        .ok_or_else(|| {
+            syn::Error::new(
+                input.span(),
+                "Missing 'source' attribute, or use #[template(\"..\")] for an inline template",
+            )
+        })
 }
 
-// fn handle_data_struct(data: &DataStruct) -> TokenStream {
-//     if let Fields::Named(ref fields) = data.fields {};
-// }
+/// Reads the templates named by `#[source = ".."]` or `#[source(view = "..", name = "..")]`,
+/// returning them with tokens that make cargo rebuild the crate when `IXML_TEMPLATE_DIR`
+/// changes.
+fn parse_source_attribute(
+    attr: &syn::Attribute,
+    options: &IxmlOptions,
+) -> Result<(
+    Vec<(syn::Ident, diagnostic::Template)>,
+    proc_macro2::TokenStream,
+)> {
+    let mut paths = Vec::new();
+    match &attr.meta {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            value:
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(path),
+                    ..
+                }),
+            ..
+        }) => paths.push((quote::format_ident!("view"), path.clone(), attr.span())),
+        syn::Meta::List(_) => attr.parse_nested_meta(|meta| {
+            let Some(name) = meta.path.get_ident().cloned() else {
+                return Err(meta.error("expected the name of a view"));
+            };
+            if paths.iter().any(|(other, _, _)| other == &name) {
+                return Err(meta.error(format!("view `{name}` is given twice")));
+            }
+            let path = meta.value()?.parse::<syn::LitStr>()?;
+            let span = path.span();
+            paths.push((name, path, span));
+            Ok(())
+        })?,
+        _ => return Err(syn::Error::new(attr.span(), "Invalid syntax")),
+    }
+    if !paths.iter().any(|(name, _, _)| name == "view") {
+        return Err(syn::Error::new(
+            attr.span(),
+            "expected a `view` template, e.g. #[source(view = \"..\")]",
+        ));
+    }
+
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(std::path::PathBuf::from)
+        .ok_or_else(|| {
+            syn::Error::new(
+                attr.span(),
+                "CARGO_MANIFEST_DIR is not set, templates are only found when built by cargo",
+            )
+        })?;
+    let templates = paths
+        .into_iter()
+        .map(|(name, path, span)| {
+            let env_dir = std::env::var_os(resolve::TEMPLATE_DIR_VAR).map(std::path::PathBuf::from);
+            let dirs = resolve::SearchDirs {
+                root: options.root.as_ref().map(|root| root.value().into()),
+                env: env_dir,
+                // The file the attribute is written in, unknown when not run by rustc
+                module: path
+                    .span()
+                    .local_file()
+                    .and_then(|file| file.canonicalize().ok())
+                    .and_then(|file| file.parent().map(|dir| dir.to_path_buf())),
+            }
+            .resolve(&manifest_dir);
+
+            let path = resolve::find_template(&path.value(), &dirs)
+                .map_err(|message| syn::Error::new(path.span(), message))?;
+            Ok((name, diagnostic::Template::read(path, span)?))
+        })
+        .collect::<Result<Vec<_>>>()?;
 
+    let var = resolve::TEMPLATE_DIR_VAR;
+    Ok((
+        templates,
+        quote!(
+            const _: ::std::option::Option<&str> = ::std::option_env!(#var);
+        ),
+    ))
+}
+
+/// Implements `IcedComponent` like the derive, but with the template given inline.
+///
+/// ```ignore
+/// #[template(r#"<Component Message="Message">..</Component>"#)]
+/// struct Counter {
+///     value: i64,
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn template(attr: TokenStream, item: TokenStream) -> TokenStream {
-    item
+    match template_impl(attr.into(), item.into()) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
 }
 
-#[cfg(test)]
-pub(crate) fn prettyprint(tokens: proc_macro2::TokenStream) -> String {
-    use syn::parse::Parse;
+fn template_impl(
+    attr: proc_macro2::TokenStream,
+    item: proc_macro2::TokenStream,
+) -> Result<proc_macro2::TokenStream> {
+    let source = syn::parse2::<syn::LitStr>(attr)?;
+    let mut input = syn::parse2::<DeriveInput>(item)?;
+    let options = parse_ixml_options(&input.attrs)?;
+    let template = diagnostic::Template::inline(source.value(), source.span());
+    let component = derive_component_from(
+        input.clone(),
+        &options,
+        vec![(quote::format_ident!("view"), template)],
+    )?;
+
+    // `ixml` is only known as an attribute to the derive
+    input.attrs.retain(|attr| !attr.path().is_ident("ixml"));
+    Ok(quote!(
+        #input
+        #component
+    ))
+}
+
+/// Declares a component whose fields are the props its template declares in `<Props>`, with a
+/// builder method for each prop starting from their defaults.
+///
+/// ```ignore
+/// component! {
+///     #[source = "badge.ixml"]
+///     pub struct Badge;
+/// }
+///
+/// let badge = Badge::new().label("Done").count(3);
+/// ```
+#[proc_macro]
+pub fn component(input: TokenStream) -> TokenStream {
+    match props::component_impl(input.into()) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
 
-    // prettyplease::unparse(&syn::parse_str::<syn::File>(stringify!(tokens)).unwrap())
+/// Builds an `Element` from an inline template, for use where `Self::Message` is the
+/// message type, e.g. in `IcedComponent::view`. Another message type can be given first.
+///
+/// ```ignore
+/// fn view(&self) -> iced::Element<'_, Self::Message> {
+///     ixml!(r#"<Text Content="{self.value}" />"#)
+/// }
+///
+/// let toolbar = ixml!(message = Message, r#"<Button Message="{Save}">Save</Button>"#);
+/// ```
+///
+/// Given a message type, the messages are checked against it like in the derive. Fields are
+/// not checked, as the macro can not see the model: mistakes are reported by rustc in the
+/// generated code instead of the template.
+#[proc_macro]
+pub fn ixml(input: TokenStream) -> TokenStream {
+    match ixml_impl(input.into()) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
 
-    stringify!(tokens).to_string()
+/// The input of `ixml!`, an optional `message = Type,` followed by the template.
+struct IxmlInput {
+    message: Option<syn::Type>,
+    source: syn::LitStr,
+}
+
+impl syn::parse::Parse for IxmlInput {
+    fn parse(input: syn::parse::ParseStream) -> Result<Self> {
+        let message = if input.peek(syn::Ident) {
+            let key = input.parse::<syn::Ident>()?;
+            if key != "message" {
+                return Err(syn::Error::new(key.span(), "expected `message = ..`"));
+            }
+            input.parse::<syn::Token![=]>()?;
+            let message = input.parse::<syn::Type>()?;
+            input.parse::<syn::Token![,]>()?;
+            Some(message)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            message,
+            source: input.parse()?,
+        })
+    }
+}
+
+fn ixml_impl(input: proc_macro2::TokenStream) -> Result<proc_macro2::TokenStream> {
+    let IxmlInput { message, source } = syn::parse2::<IxmlInput>(input)?;
+    // The content of a component, so it is wrapped in one to be parsed
+    let mut template = diagnostic::Template::inline(
+        format!("<Component>{}</Component>", source.value()),
+        source.span(),
+    );
+    let component = template
+        .parse(&iced_xml_parse::ParseOptions::strict())?
+        .component;
+    let view = iced_xml_codegen::view_component(&component).map_err(|e| template.error(e))?;
+
+    let Some(message) = message else {
+        return Ok(quote!(::iced::Element::<'_, Self::Message>::from(#view)));
+    };
+    let contents = component.content.iter().collect::<Vec<_>>();
+    let check = message_check(&message, &contents, false, source.span());
+    let view = iced_xml_codegen::replace_self_message(view, &quote!(<#message>));
+    Ok(quote!({
+        #check
+        ::iced::Element::<'_, #message>::from(#view)
+    }))
 }
 
 #[cfg(test)]
@@ -152,6 +709,522 @@ mod tests {
             struct Test;
         };
 
-        derive_component_test(input.into());
+        assert!(derive_component_test(input).is_err());
+    }
+
+    #[test]
+    fn struct_tracks_template_file() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/counter.ixml"]
+            struct Counter {
+                value: i64,
+            }
+        };
+
+        let expanded = derive_component_test(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("../tests/counter.ixml");
+
+        assert!(expanded.contains(&format!(
+            "const _: &[u8] = include_bytes!({:?});",
+            path.to_string_lossy()
+        )));
+    }
+
+    #[test]
+    fn enum_destructures_matched_variants() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/loading.ixml"]
+            enum Todos {
+                Loading,
+                Loaded(State),
+                Failed { reason: String },
+            }
+        };
+
+        let expanded = derive_component_test(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        assert!(expanded.contains("Self::Loading =>"));
+        assert!(expanded.contains("Self::Loaded(State { input_value, .. }) =>"));
+        assert!(expanded.contains("Self::Failed { reason } =>"));
+    }
+
+    #[test]
+    #[cfg(feature = "hot-reload")]
+    fn enum_reflects_its_variant_through_core() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/loading.ixml"]
+            enum Todos {
+                Loading,
+                Loaded(State),
+                Failed { reason: String },
+            }
+        };
+
+        let expanded = derive_component_test(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        assert!(expanded.contains("::iced_xml_core::reload::view("));
+        assert!(expanded.contains("fn variant(&self)"));
+        assert!(expanded.contains("Self::Loaded(_0) => {"));
+        assert!(expanded.contains("Self::Failed { reason } => {"));
+        assert!(expanded.contains("name: \"Failed\".to_string()"));
+        assert!(!expanded.contains("iced_xml_runtime"));
+    }
+
+    #[test]
+    fn enum_with_missing_variant_complains() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/loading.ixml"]
+            enum Todos {
+                Loading,
+                Loaded(State),
+                Failed { reason: String },
+                Saving,
+            }
+        };
+
+        let error = derive_component_test(input).unwrap_err();
+
+        assert!(error.to_string().contains("Self::Saving"));
+    }
+
+    #[test]
+    fn union_complains() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/counter.ixml"]
+            union Counter {
+                value: i64,
+            }
+        };
+
+        assert!(derive_component_test(input).is_err());
+    }
+
+    #[test]
+    fn aligned_template_generates_layout() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/counter.ixml"]
+            struct Counter {
+                value: i64,
+            }
+        };
+
+        let expanded = derive_component_test(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        // `AlignX` is an alias of `HorizontalAlignment`
+        assert!(expanded.contains(".center_x(::iced::Length::Fill)"));
+        assert!(expanded.contains(".padding(20f32)"));
+    }
+
+    #[test]
+    fn generic_struct_keeps_generics() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/editor.ixml"]
+            struct Editor<T: Display>
+            where
+                T: Default,
+            {
+                value: T,
+            }
+        };
+
+        let expanded = derive_component_test(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        assert!(expanded.contains(
+            "impl<T: Display> ::iced_xml_core::IcedComponent for Editor<T>\nwhere\n    T: Default,\n{"
+        ));
+        assert!(expanded.contains("T::default().to_string()"));
+    }
+
+    #[test]
+    fn generic_model_gets_generics() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/editor.ixml"]
+            #[ixml(model = Editor)]
+            struct Editor<T: Display + Default> {
+                value: T,
+            }
+        };
+
+        let expanded = derive_component_test(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        assert!(expanded.contains("let _: &Editor<T> = self;"));
+    }
+
+    #[test]
+    fn struct_with_lifetime_keeps_lifetime() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/counter.ixml"]
+            struct Counter<'a> {
+                value: &'a i64,
+            }
+        };
+
+        let expanded = derive_component_test(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        assert!(expanded.contains("impl<'a> ::iced_xml_core::IcedComponent for Counter<'a> {"));
+    }
+
+    #[test]
+    fn message_defaults_to_unit() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/greeting.ixml"]
+            struct Greeting;
+        };
+
+        let expanded = derive_component_test(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        assert!(expanded.contains("type Message = ();"));
+        assert!(expanded.contains("let _: &Greeting = self;"));
+    }
+
+    #[test]
+    fn sent_messages_are_matched_against_the_message_type() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/counter.ixml"]
+            #[ixml(message = messages::Message<i64>)]
+            struct Counter {
+                value: i64,
+            }
+        };
+
+        let expanded = derive_component_test(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        assert!(expanded.contains("messages::Message::Increment { .. } => {}"));
+        assert!(expanded.contains("messages::Message::Decrement { .. } => {}"));
+        assert!(expanded.contains("_ => {}"));
+    }
+
+    #[test]
+    fn deny_unused_messages_matches_exhaustively() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/counter.ixml"]
+            #[ixml(deny_unused_messages)]
+            struct Counter {
+                value: i64,
+            }
+        };
+
+        let expanded = derive_component_test(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        assert!(expanded.contains("Message::Decrement { .. } => {}"));
+        assert!(!expanded.contains("_ => {}"));
+    }
+
+    #[test]
+    fn ixml_macro_checks_given_message_type() {
+        let expanded = ixml_impl(quote!(
+            message = messages::Message<i64>,
+            r#"<Button Message="{Set(1)}">Reset</Button>"#
+        ))
+        .unwrap();
+        let expanded = iced_xml_codegen::expr_to_source(expanded).unwrap();
+
+        assert!(expanded.contains("messages::Message::Set { .. } => {}"));
+        assert!(expanded.contains("<messages::Message<i64>>::Set(1)"));
+        assert!(!expanded.contains("Self::Message"));
+    }
+
+    #[test]
+    fn ixml_attribute_overrides_template_types() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/counter.ixml"]
+            #[ixml(message = messages::Message<i64>, model = Counter)]
+            struct Counter {
+                value: i64,
+            }
+        };
+
+        let expanded = derive_component_test(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        assert!(expanded.contains("type Message = messages::Message<i64>;"));
+        assert!(expanded.contains("let _: &Counter = self;"));
+    }
+
+    #[test]
+    fn invalid_message_type_complains() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/invalid_message.ixml"]
+            struct Counter;
+        };
+
+        let error = derive_component_test(input).unwrap_err();
+
+        let error = error.to_string();
+        assert!(error.starts_with("in attribute 'Message': invalid type 'not a type!'"));
+        assert!(error.contains("\n --> tests/invalid_message.ixml:2:12"));
+    }
+
+    #[test]
+    fn unknown_ixml_option_complains() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/counter.ixml"]
+            #[ixml(mesage = Message)]
+            struct Counter;
+        };
+
+        let error = derive_component_test(input).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "expected `message`, `model`, `root`, `lenient` or `deny_unused_messages`"
+        );
+    }
+
+    #[test]
+    fn invalid_expression_points_into_template() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/invalid_expr.ixml"]
+            struct Counter {
+                value: i64,
+            }
+        };
+
+        let error = derive_component_test(input).unwrap_err().to_string();
+
+        assert!(error.starts_with("in attribute 'Content': invalid expression '{self.value +}'"));
+        assert!(error.contains(
+            " --> tests/invalid_expr.ixml:4:21
+  |
+4 |     <Text Size=\"50\" Content=\"{self.value +}\" />
+  |                     ^^^^^^^^^^^^^^^^^^^^^^^^"
+        ));
+    }
+
+    #[test]
+    fn invalid_xml_points_into_template() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/invalid_xml.ixml"]
+            struct Counter;
+        };
+
+        let error = derive_component_test(input).unwrap_err().to_string();
+
+        assert!(error.starts_with("invalid xml"));
+        assert!(error.contains("tests/invalid_xml.ixml:5:"));
+    }
+
+    #[test]
+    fn unknown_field_points_into_template() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "../tests/typo.ixml"]
+            struct Counter {
+                value: i64,
+            }
+        };
+
+        let error = derive_component_test(input).unwrap_err().to_string();
+
+        assert!(error.starts_with(
+            "in attribute 'Content': no field 'valeu' on 'Counter', did you mean 'value'?"
+        ));
+        assert!(error.contains("tests/typo.ixml:5:21"));
+    }
+
+    #[test]
+    fn template_attribute_implements_component() {
+        let attr = quote!(
+            r#"<Component Message="Message">
+  <Button Message="{Increment}">Increment</Button>
+</Component>"#
+        );
+        let item = quote! {
+            #[ixml(model = Counter)]
+            struct Counter {
+                value: i64,
+            }
+        };
+
+        let expanded = template_impl(attr, item).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        assert!(expanded.contains("struct Counter {\n    value: i64,\n}"));
+        assert!(!expanded.contains("#[ixml"));
+        assert!(expanded.contains("type Message = Message;"));
+        assert!(expanded.contains("on_press(Self::Message::Increment)"));
+        assert!(!expanded.contains("include_bytes!"));
+    }
+
+    #[test]
+    fn template_attribute_points_into_literal() {
+        let attr = quote!("<Component>\n  <Text Content=\"{self.valeu}\" />\n</Component>");
+        let item = quote! {
+            struct Counter {
+                value: i64,
+            }
+        };
+
+        let error = template_impl(attr, item).unwrap_err().to_string();
+
+        assert!(error.contains("did you mean 'value'?"));
+        assert!(error.contains(" --> inline template:2:9"));
+    }
+
+    #[test]
+    fn lenient_template_warns() {
+        let source = "<Component>\n  <Column>\n    <Slider />\n    <Text Sise=\"5\">Hi</Text>\n  </Column>\n</Component>";
+        let item = quote! {
+            #[ixml(lenient)]
+            struct Counter;
+        };
+
+        let expanded = template_impl(quote!(#source), item.clone())
+            .unwrap()
+            .to_string();
+
+        assert_eq!(expanded.matches("struct TemplateWarning").count(), 2);
+        assert!(expanded.contains("unknown element 'Slider'"));
+        assert!(expanded.contains("did you mean 'Size'?"));
+        let strict = quote! {
+            struct Counter;
+        };
+        let error = template_impl(quote!(#source), strict)
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("unknown element 'Slider'"));
+    }
+
+    #[test]
+    fn ixml_macro_builds_element() {
+        let input = quote!(r#"<Row><Text Content="{self.value}" /></Row>"#);
+
+        let expanded = ixml_impl(input).unwrap();
+        let expected = quote!(::iced::Element::<'_, Self::Message>::from(
+            ::iced::widget::row![::iced::widget::text(self.value).size(10f32)]
+        ));
+
+        assert_eq!(
+            iced_xml_codegen::expr_to_source(expanded).unwrap(),
+            iced_xml_codegen::expr_to_source(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn ixml_root_resolves_template() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "counter.ixml"]
+            #[ixml(root = "tests")]
+            struct Counter {
+                value: i64,
+            }
+        };
+
+        let expanded = derive_component_test(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        assert!(expanded.contains(&format!(
+            "include_bytes!({:?})",
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("tests/counter.ixml")
+                .to_string_lossy()
+        )));
+        assert!(expanded.contains("option_env!(\"IXML_TEMPLATE_DIR\")"));
+    }
+
+    #[test]
+    fn missing_template_lists_searched_dirs() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source = "counter.ixml"]
+            struct Counter;
+        };
+
+        let error = derive_component_test(input).unwrap_err().to_string();
+
+        assert!(error.starts_with("could not find component file 'counter.ixml', searched in:"));
+        assert!(error.contains(&format!("\n  {}/src\n", env!("CARGO_MANIFEST_DIR"))));
+    }
+
+    #[test]
+    fn named_views_generate_methods() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source(view = "../tests/counter.ixml", compact = "../tests/counter_compact.ixml")]
+            pub struct Counter {
+                value: i64,
+            }
+        };
+
+        let expanded = derive_component_test(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        assert!(expanded.contains("fn view(&self) -> ::iced::Element<'_, Self::Message> {"));
+        assert!(expanded.contains(
+            "impl Counter {\n    pub fn view_compact(&self) -> ::iced::Element<'_, Message> {"
+        ));
+        // Macro arguments are not formatted
+        assert!(
+            expanded
+                .replace(char::is_whitespace, "")
+                .contains(".on_press(<Selfas::iced_xml_core::IcedComponent>::Message::Decrement)")
+        );
+        assert!(expanded.contains("counter_compact.ixml\""));
+    }
+
+    #[test]
+    fn named_views_require_view() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source(compact = "../tests/counter_compact.ixml")]
+            struct Counter {
+                value: i64,
+            }
+        };
+
+        let error = derive_component_test(input).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "expected a `view` template, e.g. #[source(view = \"..\")]"
+        );
+    }
+
+    #[test]
+    fn named_views_share_message_type() {
+        let input = quote! {
+            #[derive(IcedComponent)]
+            #[source(view = "../tests/greeting.ixml", compact = "../tests/counter_compact.ixml")]
+            struct Counter {
+                value: i64,
+            }
+        };
+
+        let error = derive_component_test(input).unwrap_err().to_string();
+
+        assert!(error.starts_with(
+            "in attribute 'Message': all views share the message type '()', but this is 'Message'"
+        ));
+        assert!(error.contains("tests/counter_compact.ixml:2:12"));
     }
 }
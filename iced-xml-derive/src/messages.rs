@@ -0,0 +1,100 @@
+use iced_xml_core::{ComponentNode, Node};
+
+use iced_xml_codegen::{is_iced_expr, trim_iced_expr};
+
+/// Collects the variants of the messages sent by the template, in order of appearance, e.g.
+/// `Set` for `Message="{Set(1)}"`.
+pub(crate) fn message_variants(node: &ComponentNode) -> Vec<syn::Ident> {
+    let mut variants = Vec::new();
+    collect_message_variants(node, &mut variants);
+    variants
+}
+
+fn collect_message_variants(node: &ComponentNode, variants: &mut Vec<syn::Ident>) {
+    match &node.node {
+        Node::Button(button_node) => {
+            if let Some(variant) = message_variant(&button_node.message)
+                && !variants.contains(&variant)
+            {
+                variants.push(variant);
+            }
+        }
+        Node::Text(_) | Node::Placeholder(_) => {}
+        Node::Column(column_node) => column_node
+            .content
+            .iter()
+            .for_each(|c| collect_message_variants(c, variants)),
+        Node::Row(row_node) => row_node
+            .content
+            .iter()
+            .for_each(|c| collect_message_variants(c, variants)),
+        Node::Match(match_node) => match_node
+            .cases
+            .iter()
+            .filter_map(|case| case.content.as_deref())
+            .for_each(|c| collect_message_variants(c, variants)),
+    }
+}
+
+/// The variant a message is constructed from. Messages which are not valid expressions are
+/// reported by the code generation.
+fn message_variant(message: &str) -> Option<syn::Ident> {
+    let message = if is_iced_expr(message) {
+        trim_iced_expr(message)
+    } else {
+        message
+    };
+    let path = match syn::parse_str::<syn::Expr>(message).ok()? {
+        syn::Expr::Path(path) => path.path,
+        syn::Expr::Call(call) => match *call.func {
+            syn::Expr::Path(path) => path.path,
+            _ => return None,
+        },
+        syn::Expr::Struct(expr) => expr.path,
+        _ => return None,
+    };
+
+    path.segments.first().map(|segment| segment.ident.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use iced_xml_core::{ButtonNode, ColumnNode, NodeLayout, Spans};
+
+    use super::*;
+
+    fn buttons(messages: &[&str]) -> ComponentNode {
+        let button = |message: &&str| {
+            Box::new(ComponentNode {
+                node: Node::Button(ButtonNode {
+                    content: "Click".to_string(),
+                    message: message.to_string(),
+                }),
+                layout: NodeLayout::default(),
+                spans: Spans::default(),
+            })
+        };
+
+        ComponentNode {
+            node: Node::Column(ColumnNode {
+                content: messages.iter().map(button).collect(),
+            }),
+            layout: NodeLayout::default(),
+            spans: Spans::default(),
+        }
+    }
+
+    #[test]
+    fn collects_message_variants() {
+        let variants = message_variants(&buttons(&[
+            "{Increment}",
+            "{Set(1)}",
+            "{Edit { id: 2 }}",
+            "{Increment}",
+            "",
+        ]));
+
+        let variants = variants.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        assert_eq!(variants, ["Increment", "Set", "Edit"]);
+    }
+}
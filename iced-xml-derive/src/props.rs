@@ -0,0 +1,226 @@
+use iced_xml_codegen::{TemplateError, is_iced_expr, trim_iced_expr};
+use iced_xml_core::PropNode;
+use quote::quote;
+use syn::{DeriveInput, Result, spanned::Spanned};
+
+use crate::{
+    derive_component_from, diagnostic::Template, find_source_attribute, parse_ixml_options,
+    parse_source_attribute,
+};
+
+/// Declares the struct of a component with a field for each prop of its template, a builder
+/// starting from their defaults, and implements `IcedComponent` for it.
+pub(crate) fn component_impl(input: proc_macro2::TokenStream) -> Result<proc_macro2::TokenStream> {
+    let mut input = syn::parse2::<DeriveInput>(input)?;
+    let syn::Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(input.span(), "expected a struct"));
+    };
+    if !matches!(data.fields, syn::Fields::Unit) {
+        return Err(syn::Error::new(
+            data.fields.span(),
+            "expected a unit struct, its fields are the props of the template",
+        ));
+    }
+    let options = parse_ixml_options(&input.attrs)?;
+    let (mut templates, track_env) =
+        parse_source_attribute(find_source_attribute(&input)?, &options)?;
+    let (_, template) = templates
+        .iter_mut()
+        .find(|(name, _)| name == "view")
+        .expect("the source attribute has a `view` template");
+    let mut props = Vec::new();
+    for prop in template.parse(&options.parse_options())?.component.props {
+        props.push(Prop::new(&prop, &props, template)?);
+    }
+
+    let fields = props
+        .iter()
+        .map(|Prop { name, ty, .. }| quote!(pub #name: #ty));
+    if let syn::Data::Struct(data) = &mut input.data {
+        data.fields = syn::Fields::Named(syn::parse_quote!({ #(#fields),* }));
+    }
+    let component = derive_component_from(input.clone(), &options, templates)?;
+
+    input
+        .attrs
+        .retain(|attr| !attr.path().is_ident("source") && !attr.path().is_ident("ixml"));
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let defaults = props
+        .iter()
+        .map(|Prop { name, default, .. }| quote!(#name: #default));
+    let setters = props.iter().map(|Prop { name, ty, .. }| {
+        quote!(
+            pub fn #name(mut self, #name: impl ::std::convert::Into<#ty>) -> Self {
+                self.#name = #name.into();
+                self
+            }
+        )
+    });
+
+    Ok(quote!(
+        #input
+
+        impl #impl_generics ::std::default::Default for #name #ty_generics #where_clause {
+            fn default() -> Self {
+                Self {
+                    #(#defaults),*
+                }
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The component with the defaults of its props.
+            pub fn new() -> Self {
+                ::std::default::Default::default()
+            }
+
+            #(#setters)*
+        }
+
+        #component
+        #track_env
+    ))
+}
+
+/// A prop of a template as rust tokens.
+struct Prop {
+    name: syn::Ident,
+    ty: syn::Type,
+    default: proc_macro2::TokenStream,
+}
+
+/// Names of the methods generated for every component, which props can not have.
+const RESERVED: &[&str] = &["new", "default"];
+
+impl Prop {
+    /// Checks a prop declared after `declared`.
+    fn new(prop: &PropNode, declared: &[Prop], template: &Template) -> Result<Self> {
+        let error = |attribute, value: &str, message: String| {
+            template.error(TemplateError::new(attribute, value, message).with_spans(&prop.spans))
+        };
+        let name = syn::parse_str::<syn::Ident>(&prop.name)
+            .map_err(|e| error("Name", &prop.name, format!("invalid prop name: {e}")))?;
+        if RESERVED.contains(&prop.name.as_str()) {
+            return Err(error(
+                "Name",
+                &prop.name,
+                format!("a prop can not be named '{name}', which is a method of every component"),
+            ));
+        }
+        if declared.iter().any(|declared| declared.name == name) {
+            return Err(error(
+                "Name",
+                &prop.name,
+                format!("prop '{name}' is declared twice"),
+            ));
+        }
+        let ty = syn::parse_str::<syn::Type>(&prop.ty)
+            .map_err(|e| error("Type", &prop.ty, format!("invalid type: {e}")))?;
+        let default = match prop.default.as_deref() {
+            None => quote!(::std::default::Default::default()),
+            Some(default) if is_iced_expr(default) => {
+                let expr = syn::parse_str::<syn::Expr>(trim_iced_expr(default))
+                    .map_err(|e| error("Default", default, format!("invalid expression: {e}")))?;
+                quote!(#expr)
+            }
+            // Text, like in other attributes, is a string
+            Some(default) => quote!(::std::convert::Into::into(#default)),
+        };
+
+        Ok(Self { name, ty, default })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+
+    use super::*;
+
+    #[test]
+    fn can_declare_component_with_props() {
+        let input = quote! {
+            #[source = "../tests/badge.ixml"]
+            pub struct Badge;
+        };
+
+        let expanded = component_impl(input).unwrap();
+        let expanded = prettyplease::unparse(&syn::parse2(expanded).unwrap());
+
+        assert!(
+            expanded.contains("pub struct Badge {\n    pub label: String,\n    pub count: u32,\n}")
+        );
+        assert!(expanded.contains("label: ::std::convert::Into::into(\"New\")"));
+        assert!(expanded.contains("count: ::std::default::Default::default()"));
+        assert!(
+            expanded
+                .contains("pub fn count(mut self, count: impl ::std::convert::Into<u32>) -> Self")
+        );
+        assert!(expanded.contains("impl ::iced_xml_core::IcedComponent for Badge"));
+    }
+
+    fn props_error(name: &str, props: &str) -> String {
+        let path = std::env::temp_dir().join(format!(
+            "iced-xml-derive-props-{name}-{}.ixml",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            format!("<Component>\n  <Props>\n{props}\n  </Props>\n</Component>"),
+        )
+        .unwrap();
+        let path = path.display().to_string();
+        let input = quote! {
+            #[source = #path]
+            pub struct Badge;
+        };
+
+        let error = component_impl(input).unwrap_err().to_string();
+        std::fs::remove_file(path).unwrap();
+        error
+    }
+
+    #[test]
+    fn duplicate_prop_complains() {
+        let error = props_error(
+            "duplicate",
+            "    <Prop Name=\"label\" Type=\"String\" />\n    <Prop Name=\"label\" Type=\"u32\" />",
+        );
+
+        assert!(error.starts_with("in attribute 'Name': prop 'label' is declared twice"));
+        assert!(error.contains(":4:11"), "{error}");
+    }
+
+    #[test]
+    fn reserved_prop_name_complains() {
+        for name in ["new", "default"] {
+            let error = props_error(name, &format!("    <Prop Name=\"{name}\" Type=\"bool\" />"));
+
+            assert!(
+                error.starts_with(&format!(
+                    "in attribute 'Name': a prop can not be named '{name}', which is a method of every component"
+                )),
+                "{error}"
+            );
+            assert!(error.contains(":3:11"), "{error}");
+        }
+    }
+
+    #[test]
+    fn struct_with_fields_complains() {
+        let input = quote! {
+            #[source = "../tests/badge.ixml"]
+            pub struct Badge {
+                label: String,
+            }
+        };
+
+        let error = component_impl(input).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "expected a unit struct, its fields are the props of the template"
+        );
+    }
+}
@@ -0,0 +1,374 @@
+use iced_xml_codegen::{is_iced_expr, trim_iced_expr};
+use iced_xml_core::{ComponentNode, Node};
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{ToTokens, format_ident, quote};
+use syn::visit::Visit;
+
+/// A message variant a template sends, with the number of arguments it is constructed from.
+struct MessageVariant {
+    name: syn::Ident,
+    arity: usize,
+}
+
+impl MessageVariant {
+    /// The variant of `Increment`, `Toggle(i)` or `Message::Toggle(i)`.
+    fn parse(message: &str) -> Option<Self> {
+        let (path, arity) = match syn::parse_str::<syn::Expr>(message).ok()? {
+            syn::Expr::Path(path) => (path.path, 0),
+            syn::Expr::Call(call) => match *call.func {
+                syn::Expr::Path(path) => (path.path, call.args.len()),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let name = path.segments.last()?.ident.clone();
+        Some(Self { name, arity })
+    }
+}
+
+/// Collects the messages a template sends, to allow constructing them by name at runtime.
+fn collect_messages(node: &ComponentNode, messages: &mut Vec<MessageVariant>) {
+    match &node.node {
+        Node::Button(button_node) => {
+            let message = button_node.message.trim_matches(['{', '}']);
+            if let Some(message) = MessageVariant::parse(message)
+                && !messages.iter().any(|m| m.name == message.name)
+            {
+                messages.push(message);
+            }
+        }
+        Node::Text(_) | Node::Placeholder(_) => {}
+        Node::Column(column_node) => column_node
+            .content
+            .iter()
+            .for_each(|c| collect_messages(c, messages)),
+        Node::Row(row_node) => row_node
+            .content
+            .iter()
+            .for_each(|c| collect_messages(c, messages)),
+        Node::Match(match_node) => match_node
+            .cases
+            .iter()
+            .filter_map(|case| case.content.as_deref())
+            .for_each(|c| collect_messages(c, messages)),
+    }
+}
+
+/// A call without arguments a template makes, `self.name()` or `name()`.
+#[derive(Debug, PartialEq)]
+struct Call {
+    name: syn::Ident,
+    on_self: bool,
+}
+
+struct CollectCalls<'a>(&'a mut Vec<Call>);
+
+impl CollectCalls<'_> {
+    fn push(&mut self, name: &syn::Ident, on_self: bool) {
+        // `self.name()` and `name()` are both looked up as `name`, methods take precedence
+        match self.0.iter_mut().find(|call| call.name == *name) {
+            Some(call) => call.on_self |= on_self,
+            None => self.0.push(Call {
+                name: name.clone(),
+                on_self,
+            }),
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CollectCalls<'_> {
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        let on_self =
+            matches!(&*call.receiver, syn::Expr::Path(path) if path.path.is_ident("self"));
+        if on_self && call.args.is_empty() && call.turbofish.is_none() {
+            self.push(&call.method, true);
+        }
+        syn::visit::visit_expr_method_call(self, call);
+    }
+
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if call.args.is_empty()
+            && let syn::Expr::Path(path) = &*call.func
+            && let Some(name) = path.path.get_ident()
+        {
+            self.push(name, false);
+        }
+        syn::visit::visit_expr_call(self, call);
+    }
+}
+
+/// Collects the calls without arguments a template makes, to allow calling them by name at
+/// runtime. Calls with arguments aren't collected, as their parameter types are unknown here.
+fn collect_calls(node: &ComponentNode, calls: &mut Vec<Call>) {
+    let mut visit = |expr: &str| {
+        if is_iced_expr(expr)
+            && let Ok(expr) = syn::parse_str::<syn::Expr>(trim_iced_expr(expr))
+        {
+            CollectCalls(calls).visit_expr(&expr);
+        }
+    };
+    match &node.node {
+        Node::Button(button_node) => {
+            visit(&button_node.content);
+            // The message itself is constructed, only its arguments are calls
+            if let Ok(syn::Expr::Call(message)) =
+                syn::parse_str::<syn::Expr>(trim_iced_expr(&button_node.message))
+            {
+                message
+                    .args
+                    .iter()
+                    .for_each(|arg| CollectCalls(calls).visit_expr(arg));
+            }
+        }
+        Node::Text(text_node) => visit(&text_node.content),
+        Node::Placeholder(_) => {}
+        Node::Column(column_node) => column_node
+            .content
+            .iter()
+            .for_each(|c| collect_calls(c, calls)),
+        Node::Row(row_node) => row_node
+            .content
+            .iter()
+            .for_each(|c| collect_calls(c, calls)),
+        Node::Match(match_node) => {
+            visit(&match_node.value);
+            match_node
+                .cases
+                .iter()
+                .filter_map(|case| case.content.as_deref())
+                .for_each(|c| collect_calls(c, calls))
+        }
+    }
+}
+
+/// Whether the tokens mention one of the type parameters.
+fn mentions(tokens: TokenStream, params: &[&syn::Ident]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => params.contains(&&ident),
+        TokenTree::Group(group) => mentions(group.stream(), params),
+        TokenTree::Punct(_) | TokenTree::Literal(_) => false,
+    })
+}
+
+/// The fields whose type depends on a type parameter, e.g. `value: Vec<T>`.
+fn generic_fields(input: &syn::DeriveInput) -> Vec<syn::Ident> {
+    let params = input
+        .generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+    let syn::Data::Struct(data) = &input.data else {
+        return Vec::new();
+    };
+    data.fields
+        .iter()
+        .filter(|field| mentions(field.ty.to_token_stream(), &params))
+        .filter_map(|field| field.ident.clone())
+        .collect()
+}
+
+/// Generates `Reflect::variant` for an enum, so its templates can match on `self`. Fields which
+/// don't convert into a `Value`, or whose type is generic, are reflected as `None`.
+fn reflect_variant(input: &syn::DeriveInput) -> Option<TokenStream> {
+    let syn::Data::Enum(data) = &input.data else {
+        return None;
+    };
+    let params = input
+        .generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+    let arms = data.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let name = ident.to_string();
+        let (pattern, bindings, names) = match &variant.fields {
+            syn::Fields::Unit => (quote!(Self::#ident), Vec::new(), Vec::new()),
+            syn::Fields::Unnamed(fields) => {
+                let bindings = (0..fields.unnamed.len())
+                    .map(|i| format_ident!("_{i}"))
+                    .collect::<Vec<_>>();
+                let names = (0..fields.unnamed.len()).map(|i| i.to_string()).collect();
+                (quote!(Self::#ident(#(#bindings),*)), bindings, names)
+            }
+            syn::Fields::Named(fields) => {
+                let bindings = fields
+                    .named
+                    .iter()
+                    .filter_map(|field| field.ident.clone())
+                    .collect::<Vec<_>>();
+                let names = bindings.iter().map(|b| b.to_string()).collect();
+                (quote!(Self::#ident { #(#bindings),* }), bindings, names)
+            }
+        };
+        let values = variant
+            .fields
+            .iter()
+            .zip(&bindings)
+            .map(|(field, binding)| {
+                if mentions(field.ty.to_token_stream(), &params) {
+                    quote!(::std::option::Option::None)
+                } else {
+                    quote!((&Wrap(#binding)).to_value().and_then(::std::result::Result::ok))
+                }
+            });
+        quote!(
+            #[allow(unused_variables)]
+            #pattern => ::iced_xml_core::reflect::Variant {
+                name: #name.to_string(),
+                fields: ::std::vec![#((#names.to_string(), #values)),*],
+            },
+        )
+    });
+
+    Some(quote!(
+        fn variant(&self) -> ::std::option::Option<::iced_xml_core::reflect::Variant> {
+            #[allow(unused_imports)]
+            use ::iced_xml_core::__private::{ToNone, ToValue, Wrap};
+            ::std::option::Option::Some(match self {
+                #(#arms)*
+            })
+        }
+    ))
+}
+
+/// Generates a `Reflect` impl, so the component can be rendered by the runtime interpreter
+/// when its template changes. Fields and results of calls which don't convert into a `Value`
+/// are not reflected, and fields of a generic type report that they can't be.
+pub(crate) fn reflect_component(
+    input: &syn::DeriveInput,
+    message: &TokenStream,
+    fields: &[syn::Ident],
+    contents: &[&ComponentNode],
+) -> TokenStream {
+    let name = &input.ident;
+    // Whether a generic type converts into a `Value` is only known once it is instantiated
+    let generic_fields = generic_fields(input);
+    let (generic_fields, fields): (Vec<_>, Vec<_>) =
+        fields.iter().partition(|f| generic_fields.contains(f));
+    let generic_field_names = generic_fields.iter().map(|f| f.to_string());
+    let field_names = fields.iter().map(|f| f.to_string());
+
+    let mut messages = Vec::new();
+    let mut calls = Vec::new();
+    for content in contents {
+        collect_messages(content, &mut messages);
+        collect_calls(content, &mut calls);
+    }
+    let call_arms = calls.iter().map(|Call { name, on_self }| {
+        let method = name.to_string();
+        let call = if *on_self {
+            quote!(self.#name())
+        } else {
+            quote!(#name())
+        };
+        quote!((#method, []) => (&Wrap(&#call)).to_value(),)
+    });
+    // Variants with fields are constructed if all of them convert from a `Value`
+    let message_arms = messages.iter().map(|MessageVariant { name, arity }| {
+        let variant = name.to_string();
+        if *arity == 0 {
+            return quote!((#variant, []) => ::std::option::Option::Some(Self::Message::#name),);
+        }
+        let fields = (0..*arity).map(|_| quote!(_));
+        quote!(
+            (#variant, args) => {
+                let variant: fn(#(#fields),*) -> _ = Self::Message::#name;
+                (&Variant(variant)).construct(args)
+            }
+        )
+    });
+    let variant = reflect_variant(input);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote!(
+        impl #impl_generics ::iced_xml_core::reflect::Reflect for #name #ty_generics #where_clause {
+            type Message = #message;
+
+            fn field(
+                &self,
+                name: &str,
+            ) -> ::std::option::Option<
+                ::std::result::Result<::iced_xml_core::reflect::Value, ::iced_xml_core::reflect::Error>,
+            > {
+                #[allow(unused_imports)]
+                use ::iced_xml_core::__private::{ToNone, ToValue, Wrap};
+                match name {
+                    #(#field_names => (&Wrap(&self.#fields)).to_value(),)*
+                    #(#generic_field_names => ::std::option::Option::Some(::std::result::Result::Err(
+                        ::iced_xml_core::reflect::Error::GenericField(name.to_string()),
+                    )),)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            fn call(
+                &self,
+                name: &str,
+                args: &[::iced_xml_core::reflect::Value],
+            ) -> ::std::option::Option<
+                ::std::result::Result<::iced_xml_core::reflect::Value, ::iced_xml_core::reflect::Error>,
+            > {
+                #[allow(unused_imports)]
+                use ::iced_xml_core::__private::{ToNone, ToValue, Wrap};
+                match (name, args) {
+                    #(#call_arms)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            fn message(
+                &self,
+                name: &str,
+                args: &[::iced_xml_core::reflect::Value],
+            ) -> ::std::option::Option<Self::Message> {
+                #[allow(unused_imports)]
+                use ::iced_xml_core::__private::{Construct, NotConstructible, Variant};
+                match (name, args) {
+                    #(#message_arms)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            #variant
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_calls_without_arguments() {
+        let component = iced_xml_parse::parse_str(
+            r#"<Component Message="Message">
+  <Column>
+    <Text Content="{self.label()}" />
+    <Button Message="{Add(self.doubled(), self.scaled(2))}">{icon()}</Button>
+    <Text Content="{label()}" />
+  </Column>
+</Component>"#,
+        )
+        .unwrap();
+        let mut calls = Vec::new();
+        collect_calls(component.content.as_ref().unwrap(), &mut calls);
+
+        assert_eq!(
+            calls,
+            vec![
+                Call {
+                    name: syn::parse_quote!(label),
+                    on_self: true
+                },
+                Call {
+                    name: syn::parse_quote!(icon),
+                    on_self: false
+                },
+                Call {
+                    name: syn::parse_quote!(doubled),
+                    on_self: true
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming an extra directory to look up templates in.
+pub(crate) const TEMPLATE_DIR_VAR: &str = "IXML_TEMPLATE_DIR";
+
+/// Directories given by the user to look up templates in.
+#[derive(Default)]
+pub(crate) struct SearchDirs {
+    /// `#[ixml(root = "..")]`, relative to the crate
+    pub(crate) root: Option<PathBuf>,
+    /// `IXML_TEMPLATE_DIR`, relative to the crate
+    pub(crate) env: Option<PathBuf>,
+    /// The directory of the file deriving the component
+    pub(crate) module: Option<PathBuf>,
+}
+
+impl SearchDirs {
+    /// All directories a template is looked up in, in order: the user's directories, then
+    /// `src`, the crate and the workspace.
+    pub(crate) fn resolve(self, manifest_dir: &Path) -> Vec<PathBuf> {
+        let candidates = [
+            self.root.map(|root| manifest_dir.join(root)),
+            self.env.map(|env| manifest_dir.join(env)),
+            self.module,
+            Some(manifest_dir.join("src")),
+            Some(manifest_dir.to_path_buf()),
+            workspace_root(manifest_dir),
+        ];
+
+        let mut dirs = Vec::new();
+        for dir in candidates.into_iter().flatten() {
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+        dirs
+    }
+}
+
+/// The closest directory above the crate with a `[workspace]` manifest.
+fn workspace_root(manifest_dir: &Path) -> Option<PathBuf> {
+    manifest_dir
+        .ancestors()
+        .find(|dir| {
+            std::fs::read_to_string(dir.join("Cargo.toml"))
+                .is_ok_and(|manifest| manifest.lines().any(|l| l.trim() == "[workspace]"))
+        })
+        .map(Path::to_path_buf)
+}
+
+/// Finds the first of `dirs` containing the template.
+pub(crate) fn find_template(path: &str, dirs: &[PathBuf]) -> Result<PathBuf, String> {
+    dirs.iter()
+        .map(|dir| dir.join(path))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            let searched = dirs
+                .iter()
+                .map(|dir| format!("\n  {}", dir.display()))
+                .collect::<String>();
+            format!("could not find component file '{path}', searched in:{searched}")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    }
+
+    #[test]
+    fn searches_user_dirs_first() {
+        let dirs = SearchDirs {
+            root: Some("templates".into()),
+            env: Some("/tmp/templates".into()),
+            module: Some(manifest_dir().join("src")),
+        }
+        .resolve(&manifest_dir());
+
+        assert_eq!(
+            dirs,
+            [
+                manifest_dir().join("templates"),
+                PathBuf::from("/tmp/templates"),
+                manifest_dir().join("src"),
+                manifest_dir(),
+                manifest_dir().parent().unwrap().to_path_buf(),
+            ]
+        );
+    }
+
+    #[test]
+    fn can_find_workspace_relative_template() {
+        let dirs = SearchDirs::default().resolve(&manifest_dir());
+
+        assert_eq!(
+            find_template("iced-xml-derive/tests/counter.ixml", &dirs),
+            Ok(manifest_dir()
+                .parent()
+                .unwrap()
+                .join("iced-xml-derive/tests/counter.ixml"))
+        );
+    }
+
+    #[test]
+    fn missing_template_lists_searched_dirs() {
+        let dirs = SearchDirs::default().resolve(&manifest_dir());
+
+        let error = find_template("missing.ixml", &dirs).unwrap_err();
+
+        assert_eq!(
+            error,
+            format!(
+                "could not find component file 'missing.ixml', searched in:\n  {}\n  {}\n  {}",
+                manifest_dir().join("src").display(),
+                manifest_dir().display(),
+                manifest_dir().parent().unwrap().display(),
+            )
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use iced_xml_core::{ComponentNode, Node};
+use syn::visit::Visit;
+
+use iced_xml_codegen::{is_iced_expr, trim_iced_expr};
+use iced_xml_parse::did_you_mean;
+
+use crate::diagnostic::TemplateError;
+
+type Result<T> = std::result::Result<T, TemplateError>;
+
+/// Calls `f` with the name and value of every attribute in the template that holds an
+/// expression, including the text content of nodes.
+pub(crate) fn visit_expressions(
+    node: &ComponentNode,
+    f: &mut impl FnMut(&'static str, &str) -> Result<()>,
+) -> Result<()> {
+    let spanned = |result: Result<()>| result.map_err(|e| e.with_spans(&node.spans));
+    match &node.node {
+        Node::Button(button_node) => {
+            spanned(f("Content", &button_node.content))?;
+            spanned(f("Message", &button_node.message))
+        }
+        Node::Text(text_node) => spanned(f("Content", &text_node.content)),
+        Node::Placeholder(_) => Ok(()),
+        Node::Column(column_node) => column_node
+            .content
+            .iter()
+            .try_for_each(|c| visit_expressions(c, f)),
+        Node::Row(row_node) => row_node
+            .content
+            .iter()
+            .try_for_each(|c| visit_expressions(c, f)),
+        Node::Match(match_node) => {
+            spanned(f("Value", &match_node.value))?;
+            match_node
+                .cases
+                .iter()
+                .filter_map(|case| case.content.as_deref())
+                .try_for_each(|c| visit_expressions(c, f))
+        }
+    }
+}
+
+/// Collects the names of all `self.field` accesses in an expression.
+#[derive(Default)]
+struct SelfFields(Vec<syn::Ident>);
+
+impl<'ast> Visit<'ast> for SelfFields {
+    fn visit_expr_field(&mut self, node: &'ast syn::ExprField) {
+        if let syn::Expr::Path(base) = &*node.base
+            && base.path.is_ident("self")
+            && let syn::Member::Named(field) = &node.member
+        {
+            self.0.push(field.clone());
+        }
+        syn::visit::visit_expr_field(self, node);
+    }
+}
+
+/// Checks that every `{self.field}` in the template refers to a field of the model.
+pub(crate) fn check_fields(
+    node: &ComponentNode,
+    model: &syn::Ident,
+    fields: &[syn::Ident],
+) -> Result<()> {
+    visit_expressions(node, &mut |attribute, value| {
+        if !is_iced_expr(value) {
+            return Ok(());
+        }
+        // Invalid expressions are reported by the code generation
+        let Ok(expr) = syn::parse_str::<syn::Expr>(trim_iced_expr(value)) else {
+            return Ok(());
+        };
+
+        let mut accessed = SelfFields::default();
+        accessed.visit_expr(&expr);
+        match accessed.0.into_iter().find(|field| !fields.contains(field)) {
+            None => Ok(()),
+            Some(field) => Err(TemplateError::new(
+                attribute,
+                value,
+                unknown_field_message(model, &field, fields),
+            )),
+        }
+    })
+}
+
+fn unknown_field_message(model: &syn::Ident, field: &syn::Ident, fields: &[syn::Ident]) -> String {
+    unknown_name_message("field", field, model, fields)
+}
+
+/// Describes a name that is missing on `owner`, suggesting the most similar candidate.
+fn unknown_name_message(
+    kind: &str,
+    name: &syn::Ident,
+    owner: &syn::Ident,
+    candidates: &[syn::Ident],
+) -> String {
+    let message = format!("no {kind} '{name}' on '{owner}'");
+    let candidates = candidates.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+    let suggestion = did_you_mean(&name.to_string(), candidates.iter().map(String::as_str));
+
+    match suggestion {
+        Some(suggestion) => format!("{message}, did you mean '{suggestion}'?"),
+        None if candidates.is_empty() => message,
+        None => format!(
+            "{message}, available {kind}s are: {}",
+            candidates.join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iced_xml_core::{NodeLayout, Spans, TextNode};
+    use quote::format_ident;
+
+    use super::*;
+
+    fn text(content: &str) -> ComponentNode {
+        ComponentNode {
+            node: Node::Text(TextNode {
+                content: content.to_string(),
+                size: 10.0,
+                color: None,
+                font: None,
+            }),
+            layout: NodeLayout::default(),
+            spans: Spans::default(),
+        }
+    }
+
+    #[test]
+    fn unknown_field_suggests_similar_field() {
+        let fields = [format_ident!("value"), format_ident!("name")];
+
+        let error = check_fields(
+            &text("{self.valeu + 1}"),
+            &format_ident!("Counter"),
+            &fields,
+        )
+        .unwrap_err();
+
+        assert_eq!(error.attribute, "Content");
+        assert_eq!(
+            error.message,
+            "no field 'valeu' on 'Counter', did you mean 'value'?"
+        );
+    }
+
+    #[test]
+    fn unknown_field_lists_fields() {
+        let fields = [format_ident!("value"), format_ident!("name")];
+
+        let error =
+            check_fields(&text("{self.title}"), &format_ident!("Counter"), &fields).unwrap_err();
+
+        assert_eq!(
+            error.message,
+            "no field 'title' on 'Counter', available fields are: value, name"
+        );
+    }
+
+    #[test]
+    fn methods_and_literals_are_not_fields() {
+        let fields = [format_ident!("value")];
+
+        assert!(check_fields(&text("{self.value()}"), &format_ident!("Counter"), &fields).is_ok());
+        assert!(check_fields(&text("self.valeu"), &format_ident!("Counter"), &fields).is_ok());
+    }
+}
@@ -0,0 +1,168 @@
+//! The attributes each element accepts.
+//!
+//! Some attributes have shorter aliases, which can be used instead of their full name:
+//!
+//! | Alias    | Attribute             |
+//! |----------|-----------------------|
+//! | `AlignX` | `HorizontalAlignment` |
+//! | `AlignY` | `VerticalAlignment`   |
+
+use crate::{Error, Position, did_you_mean};
+
+/// Aliases and the attribute they stand for.
+pub const ALIASES: &[(&str, &str)] = &[
+    ("AlignX", "HorizontalAlignment"),
+    ("AlignY", "VerticalAlignment"),
+];
+
+pub(crate) const LAYOUT: &[&str] = &[
+    "HorizontalAlignment",
+    "VerticalAlignment",
+    "Padding",
+    "Width",
+    "Height",
+];
+
+/// The attributes of an element by their full name, `None` for unknown elements.
+pub fn element_attributes(element: &str) -> Option<Vec<&'static str>> {
+    let own: &[&str] = match element {
+        "Component" => return Some(vec!["Model", "Message"]),
+        "MatchCase" => return Some(vec!["Case", "Destructure"]),
+        "Include" => return Some(vec!["Source"]),
+        "Import" => return Some(vec!["Component", "Source"]),
+        "Template" => return Some(vec!["Name", "Params"]),
+        "Props" => return Some(Vec::new()),
+        "Prop" => return Some(vec!["Name", "Type", "Default"]),
+        "Button" => &["Content", "Message"],
+        "Text" => &["Content", "Size", "Color", "Font"],
+        "Column" | "Row" => &[],
+        "Match" => &["Value"],
+        _ => return None,
+    };
+    Some([own, LAYOUT].concat())
+}
+
+/// The full name of an attribute, which is the name itself unless it is an alias.
+pub fn canonical_name(name: &str) -> &str {
+    ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map_or(name, |(_, attribute)| attribute)
+}
+
+/// The value of an attribute given by its full name or an alias.
+pub(crate) fn attribute<'a>(node: &roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    find_attribute(node, name).map(|a| a.value())
+}
+
+/// An attribute given by its full name or an alias.
+pub(crate) fn find_attribute<'a, 'input>(
+    node: &roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Attribute<'a, 'input>> {
+    node.attributes().find(|a| canonical_name(a.name()) == name)
+}
+
+/// Finds the attributes of an element which are unknown or given twice.
+pub(crate) fn check_attributes(node: &roxmltree::Node) -> Vec<Error> {
+    match element_attributes(node.tag_name().name()) {
+        Some(allowed) => check_allowed_attributes(node, &allowed),
+        None => Vec::new(),
+    }
+}
+
+/// Finds the attributes of an element which are not allowed or given twice.
+pub(crate) fn check_allowed_attributes(node: &roxmltree::Node, allowed: &[&str]) -> Vec<Error> {
+    let element = node.tag_name().name();
+    let position = |attribute: &roxmltree::Attribute| {
+        let pos = node.document().text_pos_at(attribute.range().start);
+        Position {
+            line: pos.row,
+            column: pos.col,
+        }
+    };
+
+    let mut errors = Vec::new();
+    for (i, attribute) in node.attributes().enumerate() {
+        let name = canonical_name(attribute.name());
+        if !allowed.contains(&name) {
+            let aliases = ALIASES
+                .iter()
+                .filter(|(_, a)| allowed.contains(a))
+                .map(|(alias, _)| *alias);
+            let suggestion = did_you_mean(attribute.name(), allowed.iter().copied().chain(aliases));
+            errors.push(Error::UnknownAttribute {
+                name: attribute.name().to_string(),
+                element: element.to_string(),
+                suggestion: suggestion.map(str::to_string),
+                expected: allowed.iter().map(|a| a.to_string()).collect(),
+                position: position(&attribute),
+            });
+        } else if let Some(other) = node
+            .attributes()
+            .take(i)
+            .find(|a| canonical_name(a.name()) == name)
+        {
+            errors.push(Error::DuplicateAttribute {
+                name: attribute.name().to_string(),
+                other: other.name().to_string(),
+                position: position(&attribute),
+            });
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(source: &str) -> Result<(), Error> {
+        let document = roxmltree::Document::parse(source).unwrap();
+        check_attributes(&document.root_element())
+            .into_iter()
+            .next()
+            .map_or(Ok(()), Err)
+    }
+
+    #[test]
+    fn accepts_aliases() {
+        assert!(check(r#"<Column AlignX="Center" AlignY="Top" Padding="5" />"#).is_ok());
+        assert!(
+            check(r##"<Text Width="Fill" Height="20" Color="#fff" Font="Monospace" />"##).is_ok()
+        );
+        assert_eq!(canonical_name("AlignX"), "HorizontalAlignment");
+        assert_eq!(canonical_name("Padding"), "Padding");
+    }
+
+    #[test]
+    fn unknown_attribute_suggests_similar_attribute() {
+        let error = check(r#"<Text Sise="5">Hello</Text>"#).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unknown attribute 'Sise' on 'Text', did you mean 'Size'?"
+        );
+        assert_eq!(error.position(), Some(Position { line: 1, column: 7 }));
+    }
+
+    #[test]
+    fn unknown_attribute_lists_attributes() {
+        let error = check(r#"<Row Spacing="5" />"#).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unknown attribute 'Spacing' on 'Row', expected one of: HorizontalAlignment, VerticalAlignment, Padding, Width, Height"
+        );
+    }
+
+    #[test]
+    fn alias_and_attribute_complain() {
+        let error = check(r#"<Row AlignX="Left" HorizontalAlignment="Right" />"#).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "attribute 'HorizontalAlignment' is already given as 'AlignX'"
+        );
+    }
+}
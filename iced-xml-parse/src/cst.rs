@@ -0,0 +1,566 @@
+//! A lossless view of a template, for tools which edit templates without reformatting them.
+//!
+//! The source text is kept as is and every element, attribute, text and comment points into it
+//! with byte ranges, whitespace being whatever lies between them. Edits rewrite only the text of
+//! the node they touch and reparse the document.
+
+use std::fmt::Display;
+use std::ops::Range;
+
+use iced_xml_core::Component;
+
+use crate::{Error, parse_str};
+
+type Result<T> = std::result::Result<T, Error>;
+
+const INDENT: &str = "  ";
+
+/// A template and its syntax tree.
+#[derive(Debug, Clone)]
+pub struct Document {
+    source: String,
+    children: Vec<Child>,
+}
+
+/// The path to a node, as indices into the children of the document and its elements.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NodeId(Vec<usize>);
+
+#[derive(Debug, Clone)]
+pub enum Child {
+    Element(Element),
+    Text(Token),
+    Comment(Token),
+    ProcessingInstruction(Token),
+}
+
+/// A piece of the source which is not an element, e.g. a comment.
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub id: NodeId,
+    pub range: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Element {
+    pub id: NodeId,
+    pub name: String,
+    /// From the `<` of the start tag to the `>` of the end tag
+    pub range: Range<usize>,
+    pub name_range: Range<usize>,
+    pub start_tag: Range<usize>,
+    /// `None` for self-closing elements
+    pub end_tag: Option<Range<usize>>,
+    pub attributes: Vec<Attribute>,
+    pub children: Vec<Child>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub name: String,
+    /// The unescaped value
+    pub value: String,
+    /// From the name to the closing quote
+    pub range: Range<usize>,
+    pub name_range: Range<usize>,
+    /// The value as written, without quotes
+    pub value_range: Range<usize>,
+}
+
+impl Document {
+    pub fn parse(source: impl Into<String>) -> Result<Self> {
+        let source = source.into();
+        let document = roxmltree::Document::parse(&source)?;
+        let children = syntax_children(&document.root(), &[], &source);
+
+        Ok(Self { source, children })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// The comments, processing instructions and root element of the document.
+    pub fn children(&self) -> &[Child] {
+        &self.children
+    }
+
+    pub fn root_element(&self) -> &Element {
+        self.children
+            .iter()
+            .find_map(Child::as_element)
+            .expect("a well-formed document has a root element")
+    }
+
+    pub fn get(&self, id: &NodeId) -> Option<&Child> {
+        let (first, rest) = id.0.split_first()?;
+        rest.iter()
+            .try_fold(self.children.get(*first)?, |child, i| {
+                child.as_element()?.children.get(*i)
+            })
+    }
+
+    pub fn element(&self, id: &NodeId) -> Option<&Element> {
+        self.get(id).and_then(Child::as_element)
+    }
+
+    /// Parses the document into a component.
+    pub fn component(&self) -> Result<Component> {
+        parse_str(&self.source)
+    }
+
+    /// Sets the value of an attribute, keeping its quotes if it exists and adding it after the
+    /// other attributes if not.
+    pub fn set_attribute(&mut self, element: &NodeId, name: &str, value: &str) -> Result<()> {
+        let element = self.expect_element(element)?;
+        match element.attribute(name) {
+            Some(attribute) => {
+                let quote = self.source[attribute.value_range.end..].chars().next();
+                let value = escape_attribute(value, quote.unwrap_or('"'));
+                self.edit(attribute.value_range.clone(), &value)
+            }
+            None => {
+                let end = element
+                    .attributes
+                    .last()
+                    .map_or(element.name_range.end, |a| a.range.end);
+                self.edit(
+                    end..end,
+                    &format!(" {name}=\"{}\"", escape_attribute(value, '"')),
+                )
+            }
+        }
+    }
+
+    /// Removes an attribute and the whitespace before it, doing nothing if it does not exist.
+    pub fn remove_attribute(&mut self, element: &NodeId, name: &str) -> Result<()> {
+        let element = self.expect_element(element)?;
+        let Some(attribute) = element.attribute(name) else {
+            return Ok(());
+        };
+        let start = self.source[..attribute.range.start].trim_end().len();
+        self.edit(start..attribute.range.end, "")
+    }
+
+    /// Inserts `markup`, e.g. an element written by [`node_to_ixml`](crate::node_to_ixml), as
+    /// the `index`th element child of `parent`, indented like its siblings.
+    pub fn insert_child(&mut self, parent: &NodeId, index: usize, markup: &str) -> Result<()> {
+        let parent = self.expect_element(parent)?;
+        let siblings = parent
+            .children
+            .iter()
+            .filter_map(Child::as_element)
+            .collect::<Vec<_>>();
+        if index > siblings.len() {
+            return Err(Error::InvalidEdit(format!(
+                "can not insert child {index} into '{}' with {} element children",
+                parent.name,
+                siblings.len()
+            )));
+        }
+        let parent_indent = self.indent_of(parent.range.start).to_string();
+        let indent = match siblings.first() {
+            Some(sibling) => self.indent_of(sibling.range.start).to_string(),
+            None => format!("{parent_indent}{INDENT}"),
+        };
+        let markup = markup.trim().replace('\n', &format!("\n{indent}"));
+
+        let (range, text) = match (siblings.get(index), siblings.last(), &parent.end_tag) {
+            (Some(next), _, _) => {
+                let start = self.leading_comments_start(parent, next);
+                (start..start, format!("{markup}\n{indent}"))
+            }
+            (None, Some(previous), _) => (
+                previous.range.end..previous.range.end,
+                format!("\n{indent}{markup}"),
+            ),
+            (None, None, Some(end_tag)) => {
+                let start = self.source[..end_tag.start]
+                    .trim_end()
+                    .len()
+                    .max(parent.start_tag.end);
+                (
+                    start..end_tag.start,
+                    format!("\n{indent}{markup}\n{parent_indent}"),
+                )
+            }
+            (None, None, None) => {
+                // `<Column />` becomes `<Column>..</Column>`
+                let start = self.source[..parent.start_tag.end - "/>".len()]
+                    .trim_end()
+                    .len();
+                (
+                    start..parent.start_tag.end,
+                    format!(">\n{indent}{markup}\n{parent_indent}</{}>", parent.name),
+                )
+            }
+        };
+        self.edit(range, &text)
+    }
+
+    /// Removes a node, and its line if nothing else is on it.
+    pub fn remove(&mut self, id: &NodeId) -> Result<()> {
+        let child = self
+            .get(id)
+            .ok_or_else(|| Error::InvalidEdit(format!("no node at {id}")))?;
+        if id.0.len() == 1 && child.as_element().is_some() {
+            return Err(Error::InvalidEdit(
+                "can not remove the root element".to_string(),
+            ));
+        }
+        let range = child.range().clone();
+
+        let line_start = self.source[..range.start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.source[range.end..]
+            .find('\n')
+            .map_or(self.source.len(), |i| range.end + i + 1);
+        let own_line = self.source[line_start..range.start].trim().is_empty()
+            && self.source[range.end..line_end].trim().is_empty();
+        let range = if own_line {
+            line_start..line_end
+        } else {
+            range
+        };
+        self.edit(range, "")
+    }
+
+    fn expect_element(&self, id: &NodeId) -> Result<&Element> {
+        self.element(id)
+            .ok_or_else(|| Error::InvalidEdit(format!("no element at {id}")))
+    }
+
+    /// Where the comments directly before an element start, to keep them with the element.
+    fn leading_comments_start(&self, parent: &Element, element: &Element) -> usize {
+        parent
+            .children
+            .iter()
+            .take_while(|c| c.id() != &element.id)
+            .fold(element.range.start, |start, child| match child {
+                Child::Comment(comment) => start.min(comment.range.start),
+                Child::Text(text) if self.source[text.range.clone()].trim().is_empty() => start,
+                _ => element.range.start,
+            })
+    }
+
+    /// The whitespace before `offset` on its line, empty if there is anything else.
+    fn indent_of(&self, offset: usize) -> &str {
+        let line_start = self.source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let indent = &self.source[line_start..offset];
+        if indent.trim().is_empty() { indent } else { "" }
+    }
+
+    /// Replaces `range` of the source with `text`, leaving the document unchanged if the result
+    /// is not well-formed.
+    fn edit(&mut self, range: Range<usize>, text: &str) -> Result<()> {
+        let mut source = self.source.clone();
+        source.replace_range(range, text);
+        *self = Document::parse(source)?;
+        Ok(())
+    }
+}
+
+impl Display for Document {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Display for NodeId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = self.0.iter().map(usize::to_string).collect::<Vec<_>>();
+        write!(f, "/{}", path.join("/"))
+    }
+}
+
+impl Child {
+    pub fn id(&self) -> &NodeId {
+        match self {
+            Child::Element(element) => &element.id,
+            Child::Text(token) | Child::Comment(token) | Child::ProcessingInstruction(token) => {
+                &token.id
+            }
+        }
+    }
+
+    pub fn range(&self) -> &Range<usize> {
+        match self {
+            Child::Element(element) => &element.range,
+            Child::Text(token) | Child::Comment(token) | Child::ProcessingInstruction(token) => {
+                &token.range
+            }
+        }
+    }
+
+    pub fn as_element(&self) -> Option<&Element> {
+        match self {
+            Child::Element(element) => Some(element),
+            _ => None,
+        }
+    }
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&Attribute> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(Child::as_element)
+    }
+}
+
+fn syntax_children(node: &roxmltree::Node, id: &[usize], source: &str) -> Vec<Child> {
+    node.children()
+        .enumerate()
+        .filter_map(|(i, child)| {
+            let id = [id, &[i]].concat();
+            let token = || Token {
+                id: NodeId(id.clone()),
+                range: child.range(),
+            };
+            match child.node_type() {
+                roxmltree::NodeType::Element => {
+                    Some(Child::Element(syntax_element(&child, id.clone(), source)))
+                }
+                roxmltree::NodeType::Text => Some(Child::Text(token())),
+                roxmltree::NodeType::Comment => Some(Child::Comment(token())),
+                roxmltree::NodeType::PI => Some(Child::ProcessingInstruction(token())),
+                roxmltree::NodeType::Root => None,
+            }
+        })
+        .collect()
+}
+
+fn syntax_element(node: &roxmltree::Node, id: Vec<usize>, source: &str) -> Element {
+    let range = node.range();
+    let name_start = range.start + "<".len();
+    let name_len = source[name_start..]
+        .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+        .unwrap_or(0);
+    let name_range = name_start..name_start + name_len;
+
+    let attributes = node
+        .attributes()
+        .map(|attribute| Attribute {
+            name: source[attribute.range_qname()].to_string(),
+            value: attribute.value().to_string(),
+            range: attribute.range(),
+            name_range: attribute.range_qname(),
+            value_range: attribute.range_value(),
+        })
+        .collect::<Vec<_>>();
+
+    // Attribute values can contain `>`, but not the whitespace after them
+    let attributes_end = attributes.last().map_or(name_range.end, |a| a.range.end);
+    let start_tag_end = source[attributes_end..]
+        .find('>')
+        .map_or(range.end, |i| attributes_end + i + 1);
+    let start_tag = range.start..start_tag_end;
+    let end_tag = (!source[..start_tag_end].ends_with("/>")).then(|| {
+        let start = source[..range.end].rfind("</").unwrap_or(range.end);
+        start..range.end
+    });
+
+    Element {
+        children: syntax_children(node, &id, source),
+        id: NodeId(id),
+        name: source[name_range.clone()].to_string(),
+        range,
+        name_range,
+        start_tag,
+        end_tag,
+        attributes,
+    }
+}
+
+fn escape_attribute(value: &str, quote: char) -> String {
+    let value = value.replace('&', "&amp;").replace('<', "&lt;");
+    match quote {
+        '\'' => value.replace('\'', "&apos;"),
+        _ => value.replace('"', "&quot;"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"<?xml version="1.0"?>
+<!-- The counter -->
+<Component  Message='Message'>
+    <Column Padding="20"   AlignX="Center">
+        <Button Message="{Message::Increment}">Increment</Button>
+        <!-- the value -->
+        <Text Size="50" Content="{&amp;self.value}"/>
+    </Column>
+</Component>
+"#;
+
+    fn column(document: &Document) -> NodeId {
+        document
+            .root_element()
+            .elements()
+            .next()
+            .unwrap()
+            .id
+            .clone()
+    }
+
+    #[test]
+    fn keeps_source() {
+        let document = Document::parse(SOURCE).unwrap();
+
+        assert_eq!(document.to_string(), SOURCE);
+        assert!(matches!(document.children()[0], Child::Comment(_)));
+    }
+
+    #[test]
+    fn has_ranges() {
+        let document = Document::parse(SOURCE).unwrap();
+        let column = document.element(&column(&document)).unwrap();
+        let text = column.elements().nth(1).unwrap();
+
+        assert_eq!(
+            &SOURCE[column.start_tag.clone()],
+            r#"<Column Padding="20"   AlignX="Center">"#
+        );
+        assert_eq!(&SOURCE[column.end_tag.clone().unwrap()], "</Column>");
+        assert_eq!(text.end_tag, None);
+        let content = text.attribute("Content").unwrap();
+        assert_eq!(content.value, "{&self.value}");
+        assert_eq!(&SOURCE[content.value_range.clone()], "{&amp;self.value}");
+    }
+
+    #[test]
+    fn can_set_attribute() {
+        let mut document = Document::parse(SOURCE).unwrap();
+        let root = document.root_element().id.clone();
+        let column = column(&document);
+
+        document.set_attribute(&root, "Message", "Msg's").unwrap();
+        document.set_attribute(&column, "Padding", "a < b").unwrap();
+        document.set_attribute(&column, "Spacing", "5").unwrap();
+
+        assert_eq!(
+            document.source(),
+            SOURCE
+                .replace("Message='Message'", "Message='Msg&apos;s'")
+                .replace(
+                    r#"Padding="20"   AlignX="Center""#,
+                    r#"Padding="a &lt; b"   AlignX="Center" Spacing="5""#
+                )
+        );
+    }
+
+    #[test]
+    fn can_remove_attribute() {
+        let mut document = Document::parse(SOURCE).unwrap();
+        let column = column(&document);
+
+        document.remove_attribute(&column, "AlignX").unwrap();
+
+        assert_eq!(
+            document.source(),
+            SOURCE.replace(r#"   AlignX="Center""#, "")
+        );
+    }
+
+    #[test]
+    fn can_insert_child() {
+        let mut document = Document::parse(SOURCE).unwrap();
+        let column = column(&document);
+
+        document
+            .insert_child(&column, 1, "<Row>\n  <Text>a</Text>\n</Row>\n")
+            .unwrap();
+        document.insert_child(&column, 3, "<Text />").unwrap();
+
+        assert_eq!(
+            document.source(),
+            SOURCE
+                .replace(
+                    "        <!-- the value -->",
+                    "        <Row>\n          <Text>a</Text>\n        </Row>\n        <!-- the value -->"
+                )
+                .replace("/>\n    </Column>", "/>\n        <Text />\n    </Column>")
+        );
+        assert!(document.insert_child(&column, 5, "<Text />").is_err());
+    }
+
+    #[test]
+    fn can_insert_into_empty_element() {
+        let mut document = Document::parse("<Component>\n  <Column />\n</Component>").unwrap();
+        let column = column(&document);
+
+        document.insert_child(&column, 0, "<Text />").unwrap();
+        document.insert_child(&column, 0, "<Row></Row>").unwrap();
+        let row = document
+            .element(&column)
+            .unwrap()
+            .elements()
+            .next()
+            .unwrap()
+            .id
+            .clone();
+        document.insert_child(&row, 0, "<Text />").unwrap();
+
+        assert_eq!(
+            document.source(),
+            "<Component>\n  <Column>\n    <Row>\n      <Text />\n    </Row>\n    <Text />\n  </Column>\n</Component>"
+        );
+    }
+
+    #[test]
+    fn can_remove_node() {
+        let mut document = Document::parse(SOURCE).unwrap();
+        let column = document.element(&column(&document)).unwrap();
+        let button = column.elements().next().unwrap().id.clone();
+        let comment = column
+            .children
+            .iter()
+            .find(|c| matches!(c, Child::Comment(_)))
+            .unwrap()
+            .id()
+            .clone();
+
+        document.remove(&comment).unwrap();
+        document.remove(&button).unwrap();
+
+        assert_eq!(
+            document.source(),
+            SOURCE.replace("        <!-- the value -->\n", "").replace(
+                "        <Button Message=\"{Message::Increment}\">Increment</Button>\n",
+                ""
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_edit_keeps_document() {
+        let mut document = Document::parse(SOURCE).unwrap();
+        let root = document.root_element().id.clone();
+
+        assert!(matches!(
+            document.insert_child(&root, 0, "<Text>"),
+            Err(Error::Xml(_))
+        ));
+        assert!(matches!(document.remove(&root), Err(Error::InvalidEdit(_))));
+        assert_eq!(document.source(), SOURCE);
+    }
+
+    #[test]
+    fn edits_parse_into_component() {
+        let mut document = Document::parse(SOURCE).unwrap();
+        let column = column(&document);
+
+        document
+            .insert_child(&column, 0, "<Text>Count</Text>")
+            .unwrap();
+
+        let component = document.component().unwrap();
+        let Some(iced_xml_core::Node::Column(column)) = component.content.map(|c| c.node) else {
+            panic!("expected a column");
+        };
+        assert_eq!(column.content.len(), 3);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::Error;
+
+/// The encodings templates can be written in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+    Ascii,
+}
+
+impl Encoding {
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Latin1 => "ISO-8859-1",
+            Encoding::Ascii => "US-ASCII",
+        }
+    }
+}
+
+/// Decodes a template, detecting its encoding from a byte order mark or the `encoding` of its
+/// xml declaration, and defaulting to UTF-8. Spans point into the decoded text.
+pub fn decode(bytes: &[u8]) -> Result<String, Error> {
+    let (encoding, content) = match bytes {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => (Encoding::Utf8, rest),
+        [0xFF, 0xFE, rest @ ..] => (Encoding::Utf16Le, rest),
+        [0xFE, 0xFF, rest @ ..] => (Encoding::Utf16Be, rest),
+        // `<?` without a byte order mark
+        [0x3C, 0x00, 0x3F, 0x00, ..] => (Encoding::Utf16Le, bytes),
+        [0x00, 0x3C, 0x00, 0x3F, ..] => (Encoding::Utf16Be, bytes),
+        _ => (declared_encoding(bytes)?, bytes),
+    };
+
+    match encoding {
+        Encoding::Utf8 => String::from_utf8(content.to_vec()).map_err(|e| {
+            decode_error(
+                encoding,
+                format!("invalid byte at {}", e.utf8_error().valid_up_to()),
+            )
+        }),
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            if content.len() % 2 != 0 {
+                return Err(decode_error(encoding, "odd number of bytes".to_string()));
+            }
+            let units = content.chunks_exact(2).map(|pair| match encoding {
+                Encoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                _ => u16::from_be_bytes([pair[0], pair[1]]),
+            });
+            char::decode_utf16(units)
+                .collect::<Result<String, _>>()
+                .map(|decoded| decoded.trim_start_matches('\u{FEFF}').to_string())
+                .map_err(|e| {
+                    let unit = e.unpaired_surrogate();
+                    decode_error(encoding, format!("unpaired surrogate {unit:#06x}"))
+                })
+        }
+        Encoding::Latin1 => Ok(content.iter().map(|b| char::from(*b)).collect()),
+        Encoding::Ascii => match content.iter().position(|b| !b.is_ascii()) {
+            Some(i) => Err(decode_error(encoding, format!("invalid byte at {i}"))),
+            None => Ok(content.iter().map(|b| char::from(*b)).collect()),
+        },
+    }
+}
+
+/// The encoding named by the xml declaration of an ASCII compatible template.
+fn declared_encoding(bytes: &[u8]) -> Result<Encoding, Error> {
+    let Some(declaration) = bytes
+        .strip_prefix(b"<?xml")
+        .and_then(|rest| rest.split(|b| *b == b'>').next())
+    else {
+        return Ok(Encoding::Utf8);
+    };
+    // The declaration itself is ASCII, the rest of the template may not be
+    let declaration = String::from_utf8_lossy(declaration);
+    let Some((_, value)) = declaration.split_once("encoding") else {
+        return Ok(Encoding::Utf8);
+    };
+    let name = value
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '=')
+        .trim_start_matches(['"', '\''])
+        .split(['"', '\''])
+        .next()
+        .unwrap_or_default();
+
+    match name.to_ascii_uppercase().as_str() {
+        "UTF-8" | "UTF8" => Ok(Encoding::Utf8),
+        "ISO-8859-1" | "LATIN1" | "LATIN-1" => Ok(Encoding::Latin1),
+        "US-ASCII" | "ASCII" => Ok(Encoding::Ascii),
+        "UTF-16" | "UTF-16LE" | "UTF-16BE" => Err(Error::Decode {
+            encoding: name.to_string(),
+            message: "the template is not encoded as declared".to_string(),
+        }),
+        _ => Err(Error::Decode {
+            encoding: name.to_string(),
+            message: "unsupported encoding, expected UTF-8, UTF-16, ISO-8859-1 or US-ASCII"
+                .to_string(),
+        }),
+    }
+}
+
+fn decode_error(encoding: Encoding, message: String) -> Error {
+    Error::Decode {
+        encoding: encoding.name().to_string(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: &str = "<?xml version=\"1.0\"?><Component><Text>Grüße</Text></Component>";
+
+    fn utf16(text: &str, big_endian: bool) -> Vec<u8> {
+        text.encode_utf16()
+            .flat_map(|unit| match big_endian {
+                true => unit.to_be_bytes(),
+                false => unit.to_le_bytes(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn can_decode_utf8() {
+        assert_eq!(decode(TEMPLATE.as_bytes()).unwrap(), TEMPLATE);
+        let with_bom = [b"\xEF\xBB\xBF".as_slice(), TEMPLATE.as_bytes()].concat();
+        assert_eq!(decode(&with_bom).unwrap(), TEMPLATE);
+    }
+
+    #[test]
+    fn can_decode_utf16() {
+        let le = [b"\xFF\xFE".as_slice(), &utf16(TEMPLATE, false)].concat();
+        let be = [b"\xFE\xFF".as_slice(), &utf16(TEMPLATE, true)].concat();
+
+        assert_eq!(decode(&le).unwrap(), TEMPLATE);
+        assert_eq!(decode(&be).unwrap(), TEMPLATE);
+        assert_eq!(decode(&utf16(TEMPLATE, false)).unwrap(), TEMPLATE);
+        assert_eq!(decode(&utf16(TEMPLATE, true)).unwrap(), TEMPLATE);
+    }
+
+    #[test]
+    fn can_decode_declared_latin1() {
+        let template = b"<?xml version='1.0' encoding='ISO-8859-1'?><Text>Gr\xFC\xDFe</Text>";
+
+        assert_eq!(
+            decode(template).unwrap(),
+            "<?xml version='1.0' encoding='ISO-8859-1'?><Text>Grüße</Text>"
+        );
+    }
+
+    #[test]
+    fn invalid_bytes_complain() {
+        let error = decode(b"<Text>Gr\xFC\xDFe</Text>").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "could not decode template as UTF-8: invalid byte at 8"
+        );
+
+        let error = decode(b"<?xml version=\"1.0\" encoding=\"Shift_JIS\"?><Text />").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "could not decode template as Shift_JIS: unsupported encoding, expected UTF-8, UTF-16, ISO-8859-1 or US-ASCII"
+        );
+    }
+}
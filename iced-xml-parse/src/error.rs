@@ -0,0 +1,258 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use iced_xml_core::{ParseValueError, Span};
+
+/// A 1-based line and column in a template.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Position {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    /// The template is not in the encoding it declares, or in one that is not supported.
+    Decode {
+        encoding: String,
+        message: String,
+    },
+    /// The template is not well-formed xml.
+    Xml(roxmltree::Error),
+    /// The root element of the template is not a `Component`.
+    UnexpectedRoot {
+        name: String,
+        position: Position,
+    },
+    /// The element is not a known node.
+    UnknownElement {
+        name: String,
+        position: Position,
+    },
+    /// The element has no attribute of that name.
+    UnknownAttribute {
+        name: String,
+        element: String,
+        suggestion: Option<String>,
+        expected: Vec<String>,
+        position: Position,
+    },
+    /// The attribute is given twice, once by an alias.
+    DuplicateAttribute {
+        name: String,
+        other: String,
+        position: Position,
+    },
+    /// Text in an element which only holds other elements.
+    StrayText {
+        text: String,
+        element: String,
+        position: Position,
+    },
+    /// An element which its parent can not hold, e.g. a second element in a `MatchCase`.
+    UnexpectedChild {
+        name: String,
+        element: String,
+        /// What the parent holds instead
+        expected: &'static str,
+        position: Position,
+    },
+    /// A `Use` names a template which is not defined before it.
+    UnknownTemplate {
+        name: String,
+        suggestion: Option<String>,
+        position: Position,
+    },
+    /// A `Template` has the name of one defined before it.
+    DuplicateTemplate {
+        name: String,
+        position: Position,
+    },
+    /// The element needs an attribute which is not given.
+    MissingAttribute {
+        name: String,
+        element: String,
+        position: Position,
+    },
+    /// An included or imported template failed to load or parse.
+    Include {
+        path: PathBuf,
+        /// The `Include` or `Import` element
+        position: Position,
+        error: Box<Error>,
+    },
+    /// An imported component renders another `Model` or `Message` than the importing template,
+    /// which its content is pasted into.
+    ImportMismatch {
+        component: String,
+        /// `Model` or `Message`
+        attribute: &'static str,
+        imported: String,
+        expected: String,
+        position: Position,
+    },
+    /// A template includes itself, directly or through other templates.
+    IncludeCycle {
+        /// The templates from the outermost to the one included again
+        chain: Vec<PathBuf>,
+        position: Position,
+    },
+    /// The value of an attribute does not parse into its type.
+    InvalidValue {
+        attribute: String,
+        error: ParseValueError,
+        /// The value without quotes
+        span: Span,
+    },
+    /// An edit of a [`cst::Document`](crate::cst::Document) does not apply to it.
+    InvalidEdit(String),
+}
+
+impl Error {
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            Error::Io(_) | Error::Decode { .. } | Error::InvalidEdit(_) => None,
+            Error::Xml(e) => Some(Position {
+                line: e.pos().row,
+                column: e.pos().col,
+            }),
+            Error::UnexpectedRoot { position, .. }
+            | Error::UnknownElement { position, .. }
+            | Error::UnknownAttribute { position, .. }
+            | Error::DuplicateAttribute { position, .. }
+            | Error::StrayText { position, .. }
+            | Error::UnexpectedChild { position, .. }
+            | Error::UnknownTemplate { position, .. }
+            | Error::DuplicateTemplate { position, .. }
+            | Error::MissingAttribute { position, .. }
+            | Error::Include { position, .. }
+            | Error::ImportMismatch { position, .. }
+            | Error::IncludeCycle { position, .. } => Some(*position),
+            Error::InvalidValue { span, .. } => Some(Position {
+                line: span.start.line,
+                column: span.start.column,
+            }),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "failed to read template: {e}"),
+            Error::Decode { encoding, message } => {
+                write!(f, "could not decode template as {encoding}: {message}")
+            }
+            Error::Xml(e) => write!(f, "invalid xml: {e}"),
+            Error::UnexpectedRoot { name, .. } => {
+                write!(f, "expected a 'Component' root element, found '{name}'")
+            }
+            Error::UnknownElement { name, .. } => write!(f, "unknown element '{name}'"),
+            Error::UnknownAttribute {
+                name,
+                element,
+                suggestion,
+                expected,
+                ..
+            } => {
+                write!(f, "unknown attribute '{name}' on '{element}'")?;
+                match suggestion {
+                    Some(suggestion) => write!(f, ", did you mean '{suggestion}'?"),
+                    None if expected.is_empty() => Ok(()),
+                    None => write!(f, ", expected one of: {}", expected.join(", ")),
+                }
+            }
+            Error::DuplicateAttribute { name, other, .. } => {
+                write!(f, "attribute '{name}' is already given as '{other}'")
+            }
+            Error::StrayText { text, element, .. } => {
+                write!(f, "unexpected text '{text}' in '{element}'")
+            }
+            Error::UnexpectedChild {
+                name,
+                element,
+                expected,
+                ..
+            } => write!(f, "unexpected element '{name}' in '{element}', {expected}"),
+            Error::UnknownTemplate {
+                name, suggestion, ..
+            } => {
+                write!(f, "unknown template '{name}'")?;
+                match suggestion {
+                    Some(suggestion) => write!(f, ", did you mean '{suggestion}'?"),
+                    None => Ok(()),
+                }
+            }
+            Error::DuplicateTemplate { name, .. } => {
+                write!(f, "template '{name}' is already defined")
+            }
+            Error::MissingAttribute { name, element, .. } => {
+                write!(f, "missing attribute '{name}' on '{element}'")
+            }
+            Error::Include { path, error, .. } => {
+                write!(f, "in template '{}'", path.display())?;
+                if let Some(position) = error.position() {
+                    write!(f, " at {position}")?;
+                }
+                write!(f, ": {error}")
+            }
+            Error::ImportMismatch {
+                component,
+                attribute,
+                imported,
+                expected,
+                ..
+            } => write!(
+                f,
+                "imported component '{component}' has {attribute} '{imported}', but the importing template has '{expected}'"
+            ),
+            Error::IncludeCycle { chain, .. } => {
+                let chain = chain
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "template '{}' includes itself: {}",
+                    chain.last().map_or("", String::as_str),
+                    chain.join(" -> ")
+                )
+            }
+            Error::InvalidValue {
+                attribute, error, ..
+            } => write!(f, "in attribute '{attribute}': {error}"),
+            Error::InvalidEdit(message) => write!(f, "invalid edit: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Xml(e) => Some(e),
+            Error::InvalidValue { error, .. } => Some(error),
+            Error::Include { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Error::Io(value)
+    }
+}
+
+impl From<roxmltree::Error> for Error {
+    fn from(value: roxmltree::Error) -> Self {
+        Error::Xml(value)
+    }
+}
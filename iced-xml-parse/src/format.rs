@@ -0,0 +1,201 @@
+use std::ops::Range;
+
+use crate::Error;
+
+const INDENT: &str = "  ";
+
+/// Formats a template canonically: one element per line indented by two spaces, attributes
+/// kept in order, elements without children self-closed and text-only elements on one line.
+/// Comments are kept, whitespace between elements is not.
+pub fn format_str(source: &str) -> Result<String, Error> {
+    let document = roxmltree::Document::parse(source)?;
+
+    let mut formatted = String::new();
+    if source.trim_start().starts_with("<?xml") {
+        formatted.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    }
+    for node in document.root().children() {
+        format_node(source, &node, 0, &mut formatted);
+    }
+    Ok(formatted)
+}
+
+fn format_node(source: &str, node: &roxmltree::Node, depth: usize, out: &mut String) {
+    let indent = INDENT.repeat(depth);
+    match node.node_type() {
+        roxmltree::NodeType::Element => format_element(source, node, depth, out),
+        roxmltree::NodeType::Comment => out.push_str(&format!(
+            "{indent}<!--{}-->\n",
+            node.text().unwrap_or_default()
+        )),
+        roxmltree::NodeType::Text => {
+            let text = node.text().unwrap_or_default().trim();
+            if !text.is_empty() {
+                out.push_str(&format!("{indent}{}\n", escape_text(text)));
+            }
+        }
+        roxmltree::NodeType::PI => {
+            if let Some(pi) = node.pi() {
+                let value = pi.value.map(|v| format!(" {v}")).unwrap_or_default();
+                out.push_str(&format!("{indent}<?{}{value}?>\n", pi.target));
+            }
+        }
+        roxmltree::NodeType::Root => {}
+    }
+}
+
+fn format_element(source: &str, node: &roxmltree::Node, depth: usize, out: &mut String) {
+    let indent = INDENT.repeat(depth);
+    let (name, attributes) = start_tag(source, node.range().start);
+    let attributes = attributes
+        .into_iter()
+        .map(|(qname, value)| {
+            // Namespace declarations are not attributes to roxmltree, so they are kept as written
+            let value = match node
+                .attributes()
+                .find(|a| a.range_qname().start == qname.start)
+            {
+                Some(attribute) => escape_attribute(attribute.value()),
+                None => source[value].replace('"', "&quot;"),
+            };
+            format!(" {}=\"{value}\"", &source[qname])
+        })
+        .collect::<String>();
+
+    let children = node
+        .children()
+        .filter(|c| !(c.is_text() && c.text().unwrap_or_default().trim().is_empty()))
+        .collect::<Vec<_>>();
+    match children.as_slice() {
+        [] => out.push_str(&format!("{indent}<{name}{attributes} />\n")),
+        [text] if text.is_text() => {
+            let text = escape_text(text.text().unwrap_or_default().trim());
+            out.push_str(&format!("{indent}<{name}{attributes}>{text}</{name}>\n"));
+        }
+        children => {
+            out.push_str(&format!("{indent}<{name}{attributes}>\n"));
+            for child in children {
+                format_node(source, child, depth + 1, out);
+            }
+            out.push_str(&format!("{indent}</{name}>\n"));
+        }
+    }
+}
+
+/// The ranges of the qualified name and the value of an attribute in the source.
+type AttributeRanges = (Range<usize>, Range<usize>);
+
+/// The qualified name of the element starting at `start`, with the ranges of the qualified
+/// names and values of its attributes, including namespace declarations. The source has been
+/// parsed already, so it is well-formed.
+fn start_tag(source: &str, start: usize) -> (&str, Vec<AttributeRanges>) {
+    let is_name_end = |c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=');
+    let tag = &source[start..];
+    let name_end = tag[1..].find(is_name_end).map_or(tag.len(), |i| i + 1);
+    let name = &tag[1..name_end];
+
+    let mut attributes = Vec::new();
+    let mut pos = name_end;
+    loop {
+        pos += tag[pos..].len() - tag[pos..].trim_start().len();
+        if tag[pos..].starts_with(['/', '>']) || pos >= tag.len() {
+            break;
+        }
+        let qname_end = pos + tag[pos..].find(is_name_end).unwrap_or(0);
+        let quote_start = qname_end + tag[qname_end..].find(['"', '\'']).unwrap_or(0);
+        let quote = &tag[quote_start..quote_start + 1];
+        let value_end = quote_start + 1 + tag[quote_start + 1..].find(quote).unwrap_or(0);
+        attributes.push((
+            start + pos..start + qname_end,
+            start + quote_start + 1..start + value_end,
+        ));
+        pos = value_end + 1;
+    }
+    (name, attributes)
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_str;
+
+    #[test]
+    fn can_format_template() {
+        let source = r#"<?xml version="1.0"?>
+<Component Message="Message"><Column   Padding="20">
+<!-- Counter -->
+      <Button Message="{Increment}">
+        Increment
+      </Button><Text Content="{&amp;self.value}"></Text>
+</Column></Component>"#;
+
+        assert_eq!(
+            format_str(source).unwrap(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<Component Message="Message">
+  <Column Padding="20">
+    <!-- Counter -->
+    <Button Message="{Increment}">Increment</Button>
+    <Text Content="{&amp;self.value}" />
+  </Column>
+</Component>
+"#
+        );
+    }
+
+    #[test]
+    fn formatting_is_idempotent() {
+        let source = "<Component>\n<Row><Text>a &lt; b</Text></Row></Component>";
+
+        let formatted = format_str(source).unwrap();
+
+        assert_eq!(format_str(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn formatting_keeps_parsed_component() {
+        let sources = [
+            "<Component><Text>\n  {self.value}\n</Text></Component>",
+            "<Component><Button Message=\"{Increment}\">\n  Increment\n</Button></Component>",
+            "<Component><Text Content=\" padded \">  </Text></Component>",
+            "<Component><Row><Text Content=\"{self.value}\" /> <Text>a &lt; b</Text></Row></Component>",
+        ];
+        for source in sources {
+            let mut parsed = parse_str(source).unwrap();
+            let mut formatted = parse_str(&format_str(source).unwrap()).unwrap();
+            parsed.strip_spans();
+            formatted.strip_spans();
+
+            assert_eq!(formatted, parsed, "{source}");
+        }
+    }
+
+    #[test]
+    fn keeps_namespace_prefixes_and_declarations() {
+        let source = r#"<ui:Component xmlns:ui='urn:ui' ui:Message="Message"><ui:Text   xml:lang="en">Hi</ui:Text></ui:Component>"#;
+
+        assert_eq!(
+            format_str(source).unwrap(),
+            r#"<ui:Component xmlns:ui="urn:ui" ui:Message="Message">
+  <ui:Text xml:lang="en">Hi</ui:Text>
+</ui:Component>
+"#
+        );
+    }
+
+    #[test]
+    fn invalid_xml_complains() {
+        assert!(matches!(format_str("<Component>"), Err(Error::Xml(_))));
+    }
+}
@@ -1,2 +1,24 @@
+pub mod attributes;
+pub mod cst;
+mod decode;
+mod error;
+mod format;
+mod options;
 mod parser;
-pub use parser::{parse_file, parse_str};
+mod rewrite;
+mod source;
+mod suggest;
+mod template;
+mod write;
+pub use decode::decode;
+pub use error::{Error, Position};
+pub use format::format_str;
+pub use options::{Mode, ParseOptions, Parsed};
+pub use parser::{
+    parse_bytes, parse_bytes_with, parse_file, parse_file_with, parse_reader, parse_reader_with,
+    parse_source, parse_str, parse_str_with,
+};
+pub use rewrite::{self_field, substitute_self_fields};
+pub use source::{FileSource, MemorySource, TemplateSource};
+pub use suggest::did_you_mean;
+pub use write::{node_to_ixml, to_ixml};
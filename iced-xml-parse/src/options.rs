@@ -0,0 +1,59 @@
+use std::path::PathBuf;
+
+use iced_xml_core::{Component, FileId};
+
+use crate::Error;
+
+/// How strictly templates are checked.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// Unknown elements and attributes, invalid values and stray text are errors.
+    #[default]
+    Strict,
+    /// Unknown elements become placeholders, unknown attributes and stray text are ignored and
+    /// invalid values replaced by their default, each reported as a warning.
+    Lenient,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    pub mode: Mode,
+    /// The file spans point into
+    pub file: FileId,
+    /// Where the template was read from. Includes and imports are resolved relative to its
+    /// directory, or to the current directory without a path.
+    pub path: Option<PathBuf>,
+}
+
+impl ParseOptions {
+    pub fn strict() -> Self {
+        Self::default()
+    }
+
+    pub fn lenient() -> Self {
+        Self {
+            mode: Mode::Lenient,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_file(self, file: FileId) -> Self {
+        Self { file, ..self }
+    }
+
+    pub fn with_path(self, path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+            ..self
+        }
+    }
+}
+
+/// A parsed template, along with the problems skipped when parsing leniently.
+#[derive(Debug)]
+pub struct Parsed {
+    pub component: Component,
+    pub warnings: Vec<Error>,
+    /// The templates included or imported, with the file their spans point into.
+    pub files: Vec<(FileId, PathBuf)>,
+}
@@ -1,74 +1,596 @@
-use std::{
-    io::Seek,
-    path::{Path, PathBuf},
-};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 use iced_xml_core::{
-    ButtonNode, ColumnNode, Component, ComponentNode, HorizontalAlignment, Node, NodeLayout,
-    Padding, RowNode, TextNode, VerticalAlignment,
+    AttributeSpan, ButtonNode, ColumnNode, Component, ComponentNode, FileId, LineColumn,
+    MatchCaseNode, MatchNode, Node, NodeLayout, ParseValueError, PlaceholderNode, PropNode,
+    RowNode, Span, Spans, TemplateNode, TextNode, parse_pixels,
+};
+
+use crate::attributes::{
+    LAYOUT, attribute, canonical_name, check_allowed_attributes, check_attributes, find_attribute,
+};
+use crate::decode::decode;
+use crate::source::resolve;
+use crate::template::expand;
+use crate::{
+    Error, FileSource, Mode, ParseOptions, Parsed, Position, TemplateSource, did_you_mean,
 };
 
-fn parse_component<'a>(document: roxmltree::Document<'a>) -> Component {
-    let root = document.root();
-    let window = root.first_element_child().unwrap();
-    assert!(window.tag_name().name() == "Component");
-    let message_type = window.attribute("Message").map(|attr| attr.to_string());
-    let model_type = window.attribute("Model").map(|attr| attr.to_string());
+type Result<T> = std::result::Result<T, Error>;
+
+/// The options of a parse and the warnings collected so far.
+struct Context<'o> {
+    options: &'o ParseOptions,
+    source: &'o dyn TemplateSource,
+    warnings: Vec<Error>,
+    /// The file of the template being parsed
+    file: FileId,
+    /// The templates being parsed, from the outermost to the current one
+    chain: Vec<PathBuf>,
+    scope: Scope,
+    files: Vec<(FileId, PathBuf)>,
+}
+
+/// The names a template defines for its own elements.
+#[derive(Default)]
+struct Scope {
+    /// The contents of the imported components
+    imports: HashMap<String, Option<ComponentNode>>,
+    templates: Vec<TemplateNode>,
+}
+
+impl Context<'_> {
+    /// Fails with the error in strict mode, or keeps it as a warning in lenient mode.
+    fn report(&mut self, error: Error) -> Result<()> {
+        match self.options.mode {
+            Mode::Strict => Err(error),
+            Mode::Lenient => {
+                self.warnings.push(error);
+                Ok(())
+            }
+        }
+    }
+
+    fn span(&self, node: &roxmltree::Node, range: std::ops::Range<usize>) -> Span {
+        let line_column = |offset| {
+            let pos = node.document().text_pos_at(offset);
+            LineColumn {
+                line: pos.row,
+                column: pos.col,
+            }
+        };
+        Span {
+            file: self.file,
+            start: line_column(range.start),
+            end: line_column(range.end),
+            range,
+        }
+    }
+
+    /// The spans of an element, its attributes and its text.
+    fn spans(&self, node: &roxmltree::Node) -> Spans {
+        let attributes = node
+            .attributes()
+            .map(|attribute| AttributeSpan {
+                name: attribute.name().to_string(),
+                span: self.span(node, attribute.range()),
+                value: self.span(node, attribute.range_value()),
+            })
+            .collect();
+        let text = node
+            .first_child()
+            .filter(|c| c.is_text())
+            .map(|c| self.span(node, c.range()));
+
+        Spans {
+            node: Some(self.span(node, node.range())),
+            attributes,
+            text,
+        }
+    }
+
+    fn check_attributes(&mut self, node: &roxmltree::Node) -> Result<()> {
+        check_attributes(node)
+            .into_iter()
+            .try_for_each(|error| self.report(error))
+    }
+
+    /// Reports text in an element which only holds other elements.
+    fn check_stray_text(&mut self, node: &roxmltree::Node) -> Result<()> {
+        node.children()
+            .filter(|c| c.is_text())
+            .filter_map(|c| Some((c, c.text()?.trim())))
+            .filter(|(_, text)| !text.is_empty())
+            .try_for_each(|(c, text)| {
+                self.report(Error::StrayText {
+                    text: text.to_string(),
+                    element: node.tag_name().name().to_string(),
+                    position: position(&c),
+                })
+            })
+    }
+
+    /// Reports the child elements of an element for which `is_expected` is false, as they
+    /// would be left out.
+    fn check_children(
+        &mut self,
+        node: &roxmltree::Node,
+        expected: &'static str,
+        mut is_expected: impl FnMut(&roxmltree::Node) -> bool,
+    ) -> Result<()> {
+        node.children()
+            .filter(|c| c.is_element())
+            .filter(|c| !is_expected(c))
+            .try_for_each(|c| {
+                self.report(Error::UnexpectedChild {
+                    name: c.tag_name().name().to_string(),
+                    element: node.tag_name().name().to_string(),
+                    expected,
+                    position: position(&c),
+                })
+            })
+    }
+
+    /// Reports the elements after the first one in an element holding a single node.
+    fn check_single_child(
+        &mut self,
+        node: &roxmltree::Node,
+        is_child: impl Fn(&roxmltree::Node) -> bool,
+    ) -> Result<()> {
+        let mut first = true;
+        self.check_children(node, "expected a single element", |c| {
+            !is_child(c) || std::mem::take(&mut first)
+        })
+    }
+
+    /// Parses the value of an attribute if it is given, pointing errors at the value.
+    fn parse_value<T>(
+        &mut self,
+        node: &roxmltree::Node,
+        name: &str,
+        parse: impl FnOnce(&str) -> std::result::Result<T, ParseValueError>,
+    ) -> Result<Option<T>> {
+        let Some(attribute) = find_attribute(node, name) else {
+            return Ok(None);
+        };
+        match parse(attribute.value()) {
+            Ok(value) => Ok(Some(value)),
+            Err(error) => {
+                let span = self.span(node, attribute.range_value());
+                self.report(Error::InvalidValue {
+                    attribute: attribute.name().to_string(),
+                    error,
+                    span,
+                })?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Parses the template named by the `Source` of an `Include` or `Import` element, with its
+    /// errors and warnings wrapped to show where it was included.
+    fn include(&mut self, node: &roxmltree::Node) -> Result<Option<Component>> {
+        let Some(source) = attribute(node, "Source") else {
+            self.report(Error::MissingAttribute {
+                name: "Source".to_string(),
+                element: node.tag_name().name().to_string(),
+                position: position(node),
+            })?;
+            return Ok(None);
+        };
+        let path = resolve(self.chain.last().map(PathBuf::as_path), source);
+        if self.chain.contains(&path) {
+            let mut chain = self.chain.clone();
+            chain.push(path);
+            self.report(Error::IncludeCycle {
+                chain,
+                position: position(node),
+            })?;
+            return Ok(None);
+        }
+
+        let file = FileId(self.options.file.0 + 1 + self.files.len() as u32);
+        self.files.push((file, path.clone()));
+        let outer = (
+            std::mem::replace(&mut self.file, file),
+            std::mem::take(&mut self.scope),
+            std::mem::take(&mut self.warnings),
+        );
+        self.chain.push(path.clone());
+        let component = self
+            .source
+            .load(&path)
+            .map_err(Error::from)
+            .and_then(|bytes| {
+                let content = decode(&bytes)?;
+                parse_component(roxmltree::Document::parse(&content)?, self)
+            });
+        self.chain.pop();
+        let warnings = std::mem::replace(&mut self.warnings, outer.2);
+        (self.file, self.scope) = (outer.0, outer.1);
+
+        let wrap = |error| Error::Include {
+            path: path.clone(),
+            position: position(node),
+            error: Box::new(error),
+        };
+        self.warnings.extend(warnings.into_iter().map(wrap));
+        match component {
+            Ok(component) => Ok(Some(component)),
+            Err(error) => {
+                self.report(wrap(error))?;
+                Ok(None)
+            }
+        }
+    }
+}
+
+fn position(node: &roxmltree::Node) -> Position {
+    let pos = node.document().text_pos_at(node.range().start);
+    Position {
+        line: pos.row,
+        column: pos.col,
+    }
+}
+
+fn parse_component<'a>(document: roxmltree::Document<'a>, cx: &mut Context) -> Result<Component> {
+    let window = document.root_element();
+    if !window.has_tag_name("Component") {
+        return Err(Error::UnexpectedRoot {
+            name: window.tag_name().name().to_string(),
+            position: position(&window),
+        });
+    }
+    cx.check_attributes(&window)?;
+    cx.check_stray_text(&window)?;
+    let message_type = attribute(&window, "Message").map(|attr| attr.to_string());
+    let model_type = attribute(&window, "Model").map(|attr| attr.to_string());
 
+    for import in window.children().filter(|c| c.has_tag_name("Import")) {
+        parse_import(
+            import,
+            [("Model", &model_type), ("Message", &message_type)],
+            cx,
+        )?;
+    }
+    for template in window.children().filter(|c| c.has_tag_name("Template")) {
+        parse_template(template, cx)?;
+    }
+    let props = window
+        .children()
+        .filter(|c| c.has_tag_name("Props"))
+        .map(|c| parse_props(c, cx))
+        .collect::<Result<Vec<_>>>()?
+        .concat();
+    const DECLARATIONS: &[&str] = &["Import", "Template", "Props"];
+    cx.check_single_child(&window, |c| !DECLARATIONS.contains(&c.tag_name().name()))?;
     let content = window
-        .first_element_child()
-        .map(|node| parse_component_node(node));
+        .children()
+        .find(|c| c.is_element() && !DECLARATIONS.contains(&c.tag_name().name()))
+        .map(|c| parse_component_node(c, cx))
+        .transpose()?;
 
-    Component {
+    Ok(Component {
         content,
         message_type,
         model_type,
-    }
+        templates: cx.scope.templates.clone(),
+        props,
+        spans: cx.spans(&window),
+    })
 }
 
-fn parse_component_node<'a, 'input>(node: roxmltree::Node<'a, 'input>) -> ComponentNode {
-    let layout = parse_layout(&node);
+fn parse_component_node<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    cx: &mut Context,
+) -> Result<ComponentNode> {
     let component = match node.tag_name().name() {
-        "Button" => Node::Button(parse_button_node(node)),
-        "Text" => Node::Text(parse_text_node(node)),
-        "Column" => Node::Column(parse_column_node(node)),
-        "Row" => Node::Row(parse_row_node(node)),
-        _ => todo!(), //TODO: Especially custom components will be interesting...
+        "Button" => {
+            cx.check_children(&node, "expected only text", |_| false)?;
+            Node::Button(parse_button_node(node))
+        }
+        "Text" => Node::Text(parse_text_node(node, cx)?),
+        "Column" => Node::Column(parse_column_node(node, cx)?),
+        "Row" => Node::Row(parse_row_node(node, cx)?),
+        "Match" => Node::Match(parse_match_node(node, cx)?),
+        "Include" => {
+            cx.check_attributes(&node)?;
+            let content = cx.include(&node)?.and_then(|component| component.content);
+            return Ok(content.unwrap_or_else(|| empty_node(&node, cx)));
+        }
+        "Use" => return parse_use_node(node, cx),
+        name if cx.scope.imports.contains_key(name) => return parse_imported_node(node, cx),
+        name => {
+            cx.report(Error::UnknownElement {
+                name: name.to_string(),
+                position: position(&node),
+            })?;
+            return Ok(ComponentNode {
+                node: Node::Placeholder(PlaceholderNode {
+                    name: name.to_string(),
+                }),
+                layout: NodeLayout::default(),
+                spans: cx.spans(&node),
+            });
+        }
     };
+    cx.check_attributes(&node)?;
+    let layout = parse_layout(&node, cx)?;
 
-    ComponentNode {
+    Ok(ComponentNode {
         node: component,
         layout,
+        spans: cx.spans(&node),
+    })
+}
+
+/// Reads an `Import` of the root element, making its component usable as an element.
+///
+/// The content of the component is pasted where it is used, so it has to render the `types`
+/// of the importing template. Types are only compared when both templates declare them.
+fn parse_import<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    types: [(&'static str, &Option<String>); 2],
+    cx: &mut Context,
+) -> Result<()> {
+    cx.check_attributes(&node)?;
+    let Some(name) = attribute(&node, "Component") else {
+        return cx.report(Error::MissingAttribute {
+            name: "Component".to_string(),
+            element: "Import".to_string(),
+            position: position(&node),
+        });
+    };
+    let Some(component) = cx.include(&node)? else {
+        return Ok(());
+    };
+    let normalized = |ty: &str| ty.split_whitespace().collect::<String>();
+    let mut content = component.content;
+    for ((attribute, expected), imported) in types
+        .into_iter()
+        .zip([&component.model_type, &component.message_type])
+    {
+        if let (Some(expected), Some(imported)) = (expected, imported)
+            && normalized(expected) != normalized(imported)
+        {
+            cx.report(Error::ImportMismatch {
+                component: name.to_string(),
+                attribute,
+                imported: imported.clone(),
+                expected: expected.clone(),
+                position: position(&node),
+            })?;
+            // Rendered as an empty node when parsing leniently
+            content = None;
+        }
+    }
+    cx.scope.imports.insert(name.to_string(), content);
+    Ok(())
+}
+
+/// Reads a `Template` of the root element, which can be used by the content and the templates
+/// after it.
+fn parse_template<'a, 'input>(node: roxmltree::Node<'a, 'input>, cx: &mut Context) -> Result<()> {
+    cx.check_attributes(&node)?;
+    cx.check_stray_text(&node)?;
+    let Some(name) = attribute(&node, "Name") else {
+        return cx.report(Error::MissingAttribute {
+            name: "Name".to_string(),
+            element: "Template".to_string(),
+            position: position(&node),
+        });
+    };
+    if cx.scope.templates.iter().any(|t| t.name == name) {
+        // The first definition is kept when parsing leniently
+        return cx.report(Error::DuplicateTemplate {
+            name: name.to_string(),
+            position: position(&node),
+        });
     }
+    cx.check_single_child(&node, |_| true)?;
+    let params = attribute(&node, "Params")
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|param| !param.is_empty())
+        .map(str::to_string)
+        .collect();
+    let content = node
+        .first_element_child()
+        .map(|c| parse_component_node(c, cx).map(Box::new))
+        .transpose()?;
+
+    cx.scope.templates.push(TemplateNode {
+        name: name.to_string(),
+        params,
+        content,
+        spans: cx.spans(&node),
+    });
+    Ok(())
 }
 
-fn parse_layout<'a, 'input>(node: &roxmltree::Node<'a, 'input>) -> NodeLayout {
-    let horizontal_alignment = node
-        .attribute("HorizontalAlignment")
-        .map(|attr| attr.into())
-        .unwrap_or(HorizontalAlignment::default());
+/// Reads the `Prop`s declared by a `Props` element of the root element.
+fn parse_props<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    cx: &mut Context,
+) -> Result<Vec<PropNode>> {
+    cx.check_attributes(&node)?;
+    cx.check_stray_text(&node)?;
+    let mut props = Vec::new();
+    for prop in node.children().filter(|c| c.is_element()) {
+        if !prop.has_tag_name("Prop") {
+            cx.report(Error::UnknownElement {
+                name: prop.tag_name().name().to_string(),
+                position: position(&prop),
+            })?;
+            continue;
+        }
+        cx.check_attributes(&prop)?;
+        let (Some(name), Some(ty)) = (attribute(&prop, "Name"), attribute(&prop, "Type")) else {
+            let missing = match attribute(&prop, "Name") {
+                None => "Name",
+                Some(_) => "Type",
+            };
+            cx.report(Error::MissingAttribute {
+                name: missing.to_string(),
+                element: "Prop".to_string(),
+                position: position(&prop),
+            })?;
+            continue;
+        };
+        props.push(PropNode {
+            name: name.to_string(),
+            ty: ty.to_string(),
+            default: attribute(&prop, "Default").map(str::to_string),
+            spans: cx.spans(&prop),
+        });
+    }
+    Ok(props)
+}
 
-    let vertical_alignment = node
-        .attribute("VerticalAlignment")
-        .map(|attr| attr.into())
-        .unwrap_or(VerticalAlignment::default());
+/// Expands the template named by a `Use`, laid out by its attributes like an imported component.
+fn parse_use_node<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    cx: &mut Context,
+) -> Result<ComponentNode> {
+    cx.check_stray_text(&node)?;
+    let Some(name) = attribute(&node, "Template") else {
+        cx.report(Error::MissingAttribute {
+            name: "Template".to_string(),
+            element: "Use".to_string(),
+            position: position(&node),
+        })?;
+        return Ok(empty_node(&node, cx));
+    };
+    let Some(template) = cx.scope.templates.iter().find(|t| t.name == name).cloned() else {
+        let names = cx.scope.templates.iter().map(|t| t.name.as_str());
+        cx.report(Error::UnknownTemplate {
+            name: name.to_string(),
+            suggestion: did_you_mean(name, names).map(str::to_string),
+            position: position(&node),
+        })?;
+        return Ok(empty_node(&node, cx));
+    };
 
-    let padding = node
-        .attribute("Padding")
-        .map(|attr| attr.parse::<f32>().unwrap_or(0.0))
-        .map(|p| Padding { value: p })
-        .unwrap_or(Padding::default());
+    let allowed = [&["Template"], LAYOUT]
+        .concat()
+        .into_iter()
+        .chain(template.params.iter().map(String::as_str))
+        .collect::<Vec<_>>();
+    check_allowed_attributes(&node, &allowed)
+        .into_iter()
+        .try_for_each(|error| cx.report(error))?;
+    let mut args = HashMap::new();
+    for param in &template.params {
+        match node.attribute(param.as_str()) {
+            Some(value) => {
+                args.insert(param.as_str(), value);
+            }
+            None => cx.report(Error::MissingAttribute {
+                name: param.clone(),
+                element: "Use".to_string(),
+                position: position(&node),
+            })?,
+        }
+    }
 
-    NodeLayout {
+    let mut content = expand(&template, &args).unwrap_or_else(|| empty_node(&node, cx));
+    if node
+        .attributes()
+        .any(|a| LAYOUT.contains(&canonical_name(a.name())))
+    {
+        content.layout = parse_layout(&node, cx)?;
+    }
+    Ok(content)
+}
+
+/// The content of an imported component, laid out by the attributes of the element using it.
+fn parse_imported_node<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    cx: &mut Context,
+) -> Result<ComponentNode> {
+    check_allowed_attributes(&node, LAYOUT)
+        .into_iter()
+        .try_for_each(|error| cx.report(error))?;
+    cx.check_stray_text(&node)?;
+    let name = node.tag_name().name();
+    let mut content = match cx.scope.imports[name].clone() {
+        Some(content) => content,
+        None => empty_node(&node, cx),
+    };
+    if node.attributes().len() > 0 {
+        content.layout = parse_layout(&node, cx)?;
+    }
+    Ok(content)
+}
+
+/// Stands in for an included template without content.
+fn empty_node(node: &roxmltree::Node, cx: &Context) -> ComponentNode {
+    ComponentNode {
+        node: Node::Column(ColumnNode {
+            content: Vec::new(),
+        }),
+        layout: NodeLayout::default(),
+        spans: cx.spans(node),
+    }
+}
+
+// Boxed, like the content of `ColumnNode` and `RowNode`
+#[allow(clippy::vec_box)]
+fn parse_children<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    cx: &mut Context,
+) -> Result<Vec<Box<ComponentNode>>> {
+    cx.check_stray_text(&node)?;
+    node.children()
+        .filter(|c| c.is_element())
+        .map(|c| parse_component_node(c, cx).map(Box::new))
+        .collect()
+}
+
+fn parse_layout<'a, 'input>(
+    node: &roxmltree::Node<'a, 'input>,
+    cx: &mut Context,
+) -> Result<NodeLayout> {
+    let horizontal_alignment = cx
+        .parse_value(node, "HorizontalAlignment", str::parse)?
+        .unwrap_or_default();
+    let vertical_alignment = cx
+        .parse_value(node, "VerticalAlignment", str::parse)?
+        .unwrap_or_default();
+    let padding = cx
+        .parse_value(node, "Padding", str::parse)?
+        .unwrap_or_default();
+    let width = cx
+        .parse_value(node, "Width", str::parse)?
+        .unwrap_or_default();
+    let height = cx
+        .parse_value(node, "Height", str::parse)?
+        .unwrap_or_default();
+
+    Ok(NodeLayout {
         align_x: horizontal_alignment,
         align_y: vertical_alignment,
         padding,
+        width,
+        height,
+    })
+}
+
+/// The text of an element, trimmed like the formatter does, or its `Content` attribute if it
+/// has no text. The attribute is kept as written to allow surrounding whitespace.
+fn text_content<'a>(node: &roxmltree::Node<'a, '_>) -> &'a str {
+    match node.text().map(str::trim) {
+        Some(text) if !text.is_empty() => text,
+        _ => attribute(node, "Content").unwrap_or(""),
     }
 }
 
 fn parse_button_node<'a, 'input>(node: roxmltree::Node<'a, 'input>) -> ButtonNode {
-    let content = node.text().unwrap_or("");
-    let message = node.attribute("Message").unwrap_or("");
+    let content = text_content(&node);
+    let message = attribute(&node, "Message").unwrap_or("");
 
     ButtonNode {
         content: content.to_string(),
@@ -76,60 +598,175 @@ fn parse_button_node<'a, 'input>(node: roxmltree::Node<'a, 'input>) -> ButtonNod
     }
 }
 
-fn parse_text_node<'a, 'input>(node: roxmltree::Node<'a, 'input>) -> TextNode {
+fn parse_text_node<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    cx: &mut Context,
+) -> Result<TextNode> {
     const DEFAULT_SIZE: f32 = 10.0;
-    let content = match node.text() {
-        Some(text) => text,
-        None => node.attribute("Content").unwrap_or(""),
-    };
-    let size = node
-        .attribute("Size")
-        .map(|s| s.parse::<f32>().unwrap_or(DEFAULT_SIZE))
-        .map(|s| s)
+    cx.check_children(&node, "expected only text", |_| false)?;
+    let content = text_content(&node);
+    let size = cx
+        .parse_value(&node, "Size", |size| parse_pixels("text size", size))?
         .unwrap_or(DEFAULT_SIZE);
+    let color = cx.parse_value(&node, "Color", str::parse)?;
+    let font = cx.parse_value(&node, "Font", str::parse)?;
 
-    TextNode {
+    Ok(TextNode {
         content: content.to_string(),
         size,
-    }
+        color,
+        font,
+    })
 }
 
-fn parse_column_node<'a, 'input>(node: roxmltree::Node<'a, 'input>) -> ColumnNode {
-    let content = node
-        .descendants()
-        .filter(|d| d.is_element())
-        .skip(1)
-        .map(|c| Box::new(parse_component_node(c)))
-        .collect::<Vec<_>>();
+fn parse_column_node<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    cx: &mut Context,
+) -> Result<ColumnNode> {
+    let content = parse_children(node, cx)?;
+
+    Ok(ColumnNode { content })
+}
+
+fn parse_row_node<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    cx: &mut Context,
+) -> Result<RowNode> {
+    let content = parse_children(node, cx)?;
 
-    ColumnNode { content }
+    Ok(RowNode { content })
 }
 
-fn parse_row_node<'a, 'input>(node: roxmltree::Node<'a, 'input>) -> RowNode {
+fn parse_match_node<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    cx: &mut Context,
+) -> Result<MatchNode> {
+    cx.check_stray_text(&node)?;
+    cx.check_children(&node, "expected only 'MatchCase' elements", |c| {
+        c.has_tag_name("MatchCase")
+    })?;
+    let value = attribute(&node, "Value").unwrap_or("");
+    let cases = node
+        .children()
+        .filter(|c| c.has_tag_name("MatchCase"))
+        .map(|c| parse_match_case_node(c, cx))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(MatchNode {
+        value: value.to_string(),
+        cases,
+    })
+}
+
+fn parse_match_case_node<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    cx: &mut Context,
+) -> Result<MatchCaseNode> {
+    cx.check_attributes(&node)?;
+    cx.check_stray_text(&node)?;
+    let case = attribute(&node, "Case").unwrap_or("_");
+    let destructure = attribute(&node, "Destructure").map(|attr| attr.to_string());
+    cx.check_single_child(&node, |_| true)?;
     let content = node
-        .descendants()
-        .filter(|d| d.is_element())
-        .skip(1)
-        .map(|c| Box::new(parse_component_node(c)))
-        .collect::<Vec<_>>();
+        .first_element_child()
+        .map(|c| parse_component_node(c, cx).map(Box::new))
+        .transpose()?;
 
-    RowNode { content }
+    Ok(MatchCaseNode {
+        case: case.to_string(),
+        destructure,
+        content,
+        spans: cx.spans(&node),
+    })
 }
 
-pub fn parse_file<P: AsRef<Path>>(path: P) -> Component {
-    let content = std::fs::read_to_string(path).unwrap();
-    let document = roxmltree::Document::parse(&content).unwrap();
-    parse_component(document)
+pub fn parse_file<P: AsRef<Path>>(path: P) -> Result<Component> {
+    parse_file_with(path, &ParseOptions::default()).map(|parsed| parsed.component)
 }
 
-pub fn parse_str(content: &str) -> Component {
-    let document = roxmltree::Document::parse(content).unwrap();
-    parse_component(document)
+pub fn parse_str(content: &str) -> Result<Component> {
+    parse_str_with(content, &ParseOptions::default()).map(|parsed| parsed.component)
+}
+
+/// Parses a template in any supported encoding, see [`parse_bytes_with`].
+pub fn parse_bytes(bytes: &[u8]) -> Result<Component> {
+    parse_bytes_with(bytes, &ParseOptions::default()).map(|parsed| parsed.component)
+}
+
+pub fn parse_reader<R: Read>(reader: R) -> Result<Component> {
+    parse_reader_with(reader, &ParseOptions::default()).map(|parsed| parsed.component)
+}
+
+pub fn parse_file_with<P: AsRef<Path>>(path: P, options: &ParseOptions) -> Result<Parsed> {
+    parse_source(&FileSource, path, options)
+}
+
+/// Parses a template loaded from a [`TemplateSource`].
+pub fn parse_source<S, P>(source: &S, path: P, options: &ParseOptions) -> Result<Parsed>
+where
+    S: TemplateSource,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let content = decode(&source.load(path)?)?;
+    let options = options.clone().with_path(path);
+    parse_document(&content, &options, source)
+}
+
+pub fn parse_reader_with<R: Read>(mut reader: R, options: &ParseOptions) -> Result<Parsed> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    parse_bytes_with(&bytes, options)
+}
+
+/// Parses a template encoded as UTF-8, UTF-16, ISO-8859-1 or US-ASCII, detected from its byte
+/// order mark or the `encoding` of its xml declaration. Spans point into the decoded text.
+pub fn parse_bytes_with(bytes: &[u8], options: &ParseOptions) -> Result<Parsed> {
+    parse_str_with(&decode(bytes)?, options)
+}
+
+/// Parses a template, collecting warnings instead of failing in [`Mode::Lenient`].
+/// Includes and imports are read from files, relative to [`ParseOptions::path`].
+pub fn parse_str_with(content: &str, options: &ParseOptions) -> Result<Parsed> {
+    parse_document(content, options, &FileSource)
+}
+
+fn parse_document(
+    content: &str,
+    options: &ParseOptions,
+    source: &dyn TemplateSource,
+) -> Result<Parsed> {
+    let document = roxmltree::Document::parse(content)?;
+    let mut cx = Context {
+        options,
+        source,
+        warnings: Vec::new(),
+        file: options.file,
+        chain: options
+            .path
+            .iter()
+            .map(|path| resolve(None, path))
+            .collect(),
+        scope: Scope::default(),
+        files: Vec::new(),
+    };
+    let component = parse_component(document, &mut cx)?;
+
+    Ok(Parsed {
+        component,
+        warnings: cx.warnings,
+        files: cx.files,
+    })
 }
 
 #[cfg(test)]
 mod test {
+    use iced_xml_core::{
+        Color, FileId, Font, HorizontalAlignment, Length, Node, VerticalAlignment,
+    };
+
     use super::*;
+    use crate::MemorySource;
 
     #[test]
     fn can_parse_empty_component() {
@@ -138,7 +775,10 @@ mod test {
 </Component>
         "#;
 
-        let parsed = parse_str(raw_window);
+        let parsed = parse_str(raw_window).unwrap();
+
+        assert!(parsed.content.is_none());
+        assert_eq!(parsed.message_type.as_deref(), Some("Message"));
     }
 
     #[test]
@@ -153,7 +793,16 @@ mod test {
 </Component>
         "#;
 
-        let parsed = parse_str(raw_window);
+        let parsed = parse_str(raw_window).unwrap();
+
+        let Some(ComponentNode {
+            node: Node::Column(column),
+            ..
+        }) = parsed.content
+        else {
+            panic!("expected a column as root node");
+        };
+        assert_eq!(column.content.len(), 3);
     }
 
     #[test]
@@ -167,4 +816,609 @@ mod test {
 
         assert_eq!(parsed_button.content, "Hello".to_string());
     }
+
+    #[test]
+    fn can_parse_match_node() {
+        let raw_window = r#"<?xml version="1.0" encoding="UTF-8"?> 
+<Component Model="Todos" Message="Message">
+  <Match Value="{self}">
+    <MatchCase Case="Loading">
+      <Text Size="50">Loading...</Text>
+    </MatchCase>
+    <MatchCase Case="Loaded" Destructure="{State { input_value, .. }}">
+      <Column>
+        <Text Content="{input_value}" />
+      </Column>
+    </MatchCase>
+  </Match>
+</Component>
+        "#;
+
+        let parsed = parse_str(raw_window).unwrap();
+
+        let Some(ComponentNode {
+            node: Node::Match(match_node),
+            ..
+        }) = parsed.content
+        else {
+            panic!("expected a match as root node");
+        };
+        assert_eq!(match_node.value, "{self}");
+        assert_eq!(match_node.cases.len(), 2);
+        assert_eq!(match_node.cases[0].case, "Loading");
+        assert!(match_node.cases[0].destructure.is_none());
+        assert_eq!(
+            match_node.cases[1].destructure.as_deref(),
+            Some("{State { input_value, .. }}")
+        );
+        assert!(match_node.cases[1].content.is_some());
+    }
+
+    #[test]
+    fn nodes_have_spans() {
+        let raw_window = "<Component>\n  <Column>\n    <Button Message=\"{Go}\">Go</Button>\n  </Column>\n</Component>";
+
+        let parsed = parse_str_with(raw_window, &ParseOptions::default().with_file(FileId(3)))
+            .map(|parsed| parsed.component)
+            .unwrap();
+
+        let column = parsed.content.unwrap();
+        let Node::Column(ColumnNode { content }) = column.node else {
+            panic!("expected a column as root node");
+        };
+        let span = column.spans.node.unwrap();
+        assert_eq!(span.file, FileId(3));
+        assert_eq!(
+            &raw_window[span.range],
+            "<Column>\n    <Button Message=\"{Go}\">Go</Button>\n  </Column>"
+        );
+        assert_eq!(span.start, LineColumn { line: 2, column: 3 });
+        assert_eq!(
+            span.end,
+            LineColumn {
+                line: 4,
+                column: 12
+            }
+        );
+
+        let button = &content[0].spans;
+        let message = button.attribute("Message").unwrap();
+        assert_eq!(&raw_window[message.span.range.clone()], "Message=\"{Go}\"");
+        assert_eq!(&raw_window[message.value.range.clone()], "{Go}");
+        assert_eq!(
+            message.value.start,
+            LineColumn {
+                line: 3,
+                column: 22
+            }
+        );
+        assert_eq!(&raw_window[button.text.clone().unwrap().range], "Go");
+    }
+
+    #[test]
+    fn can_parse_aliased_layout() {
+        let parsed =
+            parse_str(r#"<Component><Column AlignX="Center" AlignY="Bottom" /></Component>"#)
+                .unwrap();
+
+        let layout = parsed.content.unwrap().layout;
+        assert_eq!(layout.align_x, HorizontalAlignment::Center);
+        assert_eq!(layout.align_y, VerticalAlignment::Bottom);
+    }
+
+    #[test]
+    fn can_parse_size_color_and_font() {
+        let parsed = parse_str(
+            r##"<Component><Text Width="FillPortion(2)" Height="40" Color="#ff000080" Font="Monospace">Hi</Text></Component>"##,
+        )
+        .unwrap();
+
+        let content = parsed.content.unwrap();
+        assert_eq!(content.layout.width, Length::FillPortion(2));
+        assert_eq!(content.layout.height, Length::Fixed(40.0));
+        let Node::Text(text) = content.node else {
+            panic!("expected a text node");
+        };
+        assert_eq!(text.color, Some(Color::rgba8(255, 0, 0, 128)));
+        assert_eq!(text.font, Some(Font::Monospace));
+    }
+
+    #[test]
+    fn unknown_attribute_complains() {
+        let raw_window = "<Component>\n  <Button Mesage=\"{Go}\">Go</Button>\n</Component>";
+
+        let error = parse_str(raw_window).unwrap_err();
+
+        assert!(matches!(
+            error,
+            Error::UnknownAttribute { ref suggestion, .. } if suggestion.as_deref() == Some("Message")
+        ));
+        assert_eq!(
+            error.position(),
+            Some(Position {
+                line: 2,
+                column: 11
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_value_complains() {
+        let raw_window = "<Component>\n  <Column AlignX=\"Middle\" />\n</Component>";
+
+        let error = parse_str(raw_window).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "in attribute 'AlignX': invalid horizontal alignment 'Middle', expected one of: Left, Center, Right, Stretch"
+        );
+        let Error::InvalidValue { ref span, .. } = error else {
+            panic!("expected an invalid value");
+        };
+        assert_eq!(&raw_window[span.range.clone()], "Middle");
+        assert_eq!(
+            error.position(),
+            Some(Position {
+                line: 2,
+                column: 19
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_number_complains() {
+        let error = parse_str(r#"<Component><Text Size="big">Hi</Text></Component>"#).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "in attribute 'Size': invalid text size 'big'"
+        );
+    }
+
+    #[test]
+    fn stray_text_complains() {
+        let error =
+            parse_str("<Component>\n  <Column>\n    Hello\n  </Column>\n</Component>").unwrap_err();
+
+        assert_eq!(error.to_string(), "unexpected text 'Hello' in 'Column'");
+        assert_eq!(error.position().map(|p| p.line), Some(2));
+    }
+
+    #[test]
+    fn lenient_parse_warns() {
+        let raw_window = r#"<Component>
+  <Column Padding="wide">
+    <Slider Value="5" />
+    Hello
+    <Text Sise="5">Hi</Text>
+  </Column>
+</Component>"#;
+
+        let parsed = parse_str_with(raw_window, &ParseOptions::lenient()).unwrap();
+
+        let warnings = parsed
+            .warnings
+            .iter()
+            .map(|w| w.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            [
+                "unexpected text 'Hello' in 'Column'",
+                "unknown element 'Slider'",
+                "unknown attribute 'Sise' on 'Text', did you mean 'Size'?",
+                "in attribute 'Padding': invalid padding 'wide'",
+            ]
+        );
+        let column = parsed.component.content.unwrap();
+        assert_eq!(column.layout.padding, Default::default());
+        let Node::Column(ColumnNode { content }) = column.node else {
+            panic!("expected a column as root node");
+        };
+        assert_eq!(
+            content[0].node,
+            Node::Placeholder(PlaceholderNode {
+                name: "Slider".to_string()
+            })
+        );
+        assert!(matches!(content[1].node, Node::Text(_)));
+    }
+
+    #[test]
+    fn can_parse_utf16_bytes() {
+        let raw_window =
+            "<?xml version=\"1.0\" encoding=\"UTF-16\"?><Component><Text>Grüße</Text></Component>";
+        let bytes = [0xFF, 0xFE]
+            .into_iter()
+            .chain(raw_window.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect::<Vec<_>>();
+
+        let parsed = parse_bytes(&bytes).unwrap();
+
+        assert!(
+            matches!(parsed.content.unwrap().node, Node::Text(text) if text.content == "Grüße")
+        );
+        assert!(parse_reader(bytes.as_slice()).is_ok());
+    }
+
+    #[test]
+    fn can_parse_from_source() {
+        let source =
+            MemorySource::new().with("ui/hello.ixml", "<Component><Text>Hi</Text></Component>");
+        let loader = |path: &Path| match path.to_str() {
+            Some("hello") => Ok(b"<Component />".to_vec()),
+            _ => Err(std::io::ErrorKind::NotFound.into()),
+        };
+
+        assert!(parse_source(&source, "ui/hello.ixml", &ParseOptions::default()).is_ok());
+        assert!(parse_source(&loader, "hello", &ParseOptions::default()).is_ok());
+        let error = parse_source(&source, "ui/missing.ixml", &ParseOptions::default()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "failed to read template: no template 'ui/missing.ixml'"
+        );
+    }
+
+    #[test]
+    fn can_include_template() {
+        let source = MemorySource::new()
+            .with(
+                "ui/main.ixml",
+                r#"<Component><Column><Include Source="parts/header.ixml" /></Column></Component>"#,
+            )
+            .with(
+                "ui/parts/header.ixml",
+                r#"<Component><Text Size="20">Todos</Text></Component>"#,
+            );
+
+        let parsed = parse_source(&source, "ui/main.ixml", &ParseOptions::default()).unwrap();
+
+        let Node::Column(column) = parsed.component.content.unwrap().node else {
+            panic!("expected a column");
+        };
+        let header = &column.content[0];
+        assert!(matches!(&header.node, Node::Text(text) if text.content == "Todos"));
+        assert_eq!(header.spans.node.as_ref().unwrap().file, FileId(1));
+        assert_eq!(
+            parsed.files,
+            vec![(FileId(1), PathBuf::from("ui/parts/header.ixml"))]
+        );
+    }
+
+    #[test]
+    fn can_import_component() {
+        let source = MemorySource::new()
+            .with(
+                "main.ixml",
+                r#"<Component>
+                    <Import Component="Task" Source="task.ixml" />
+                    <Row><Task /><Task AlignX="Center" /></Row>
+                </Component>"#,
+            )
+            .with(
+                "task.ixml",
+                r#"<Component><Button Message="Message::Done">Done</Button></Component>"#,
+            );
+
+        let parsed = parse_source(&source, "main.ixml", &ParseOptions::default()).unwrap();
+
+        let Node::Row(row) = parsed.component.content.unwrap().node else {
+            panic!("expected a row");
+        };
+        assert_eq!(row.content.len(), 2);
+        assert!(matches!(&row.content[0].node, Node::Button(button) if button.content == "Done"));
+        assert_eq!(row.content[1].layout.align_x, HorizontalAlignment::Center);
+    }
+
+    #[test]
+    fn import_of_other_model_complains() {
+        let source = MemorySource::new()
+            .with(
+                "main.ixml",
+                "<Component Model=\"Todos\" Message=\"Message\">\n  <Import Component=\"Task\" Source=\"task.ixml\" />\n  <Task />\n</Component>",
+            )
+            .with(
+                "task.ixml",
+                r#"<Component Model="Task" Message=" Message "><Text>{self.description}</Text></Component>"#,
+            );
+
+        let error = parse_source(&source, "main.ixml", &ParseOptions::strict()).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "imported component 'Task' has Model 'Task', but the importing template has 'Todos'"
+        );
+        assert_eq!(error.position(), Some(Position { line: 2, column: 3 }));
+
+        let parsed = parse_source(&source, "main.ixml", &ParseOptions::lenient()).unwrap();
+        assert_eq!(parsed.warnings.len(), 1);
+        let content = parsed.component.content.unwrap();
+        assert!(
+            matches!(content.node, Node::Column(ColumnNode { ref content }) if content.is_empty())
+        );
+    }
+
+    #[test]
+    fn include_error_shows_include_chain() {
+        let source = MemorySource::new()
+            .with(
+                "main.ixml",
+                r#"<Component><Include Source="list.ixml" /></Component>"#,
+            )
+            .with(
+                "list.ixml",
+                "<Component>\n  <Column><Include Source=\"item.ixml\" /></Column>\n</Component>",
+            )
+            .with("item.ixml", "<Component><Txt /></Component>");
+
+        let error = parse_source(&source, "main.ixml", &ParseOptions::default()).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "in template 'list.ixml' at 2:11: in template 'item.ixml' at 1:12: unknown element 'Txt'"
+        );
+        assert_eq!(
+            error.position(),
+            Some(Position {
+                line: 1,
+                column: 12
+            })
+        );
+    }
+
+    #[test]
+    fn include_cycle_complains() {
+        let source = MemorySource::new()
+            .with(
+                "main.ixml",
+                r#"<Component><Include Source="a.ixml" /></Component>"#,
+            )
+            .with(
+                "a.ixml",
+                r#"<Component><Include Source="./main.ixml" /></Component>"#,
+            );
+
+        let error = parse_source(&source, "main.ixml", &ParseOptions::default()).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "in template 'a.ixml' at 1:12: template 'main.ixml' includes itself: main.ixml -> a.ixml -> main.ixml"
+        );
+    }
+
+    #[test]
+    fn lenient_include_warns() {
+        let source = MemorySource::new()
+            .with(
+                "main.ixml",
+                r#"<Component><Row><Include Source="missing.ixml" /><Include Source="part.ixml" /></Row></Component>"#,
+            )
+            .with("part.ixml", r#"<Component><Text Size="big">Hi</Text></Component>"#);
+
+        let parsed = parse_source(&source, "main.ixml", &ParseOptions::lenient()).unwrap();
+
+        let warnings = parsed
+            .warnings
+            .iter()
+            .map(|w| w.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            warnings,
+            [
+                "in template 'missing.ixml': failed to read template: no template 'missing.ixml'",
+                "in template 'part.ixml' at 1:24: in attribute 'Size': invalid text size 'big'",
+            ]
+        );
+        let Node::Row(row) = parsed.component.content.unwrap().node else {
+            panic!("expected a row");
+        };
+        assert_eq!(row.content.len(), 2);
+    }
+
+    #[test]
+    fn can_use_template() {
+        let raw_window = r#"<Component>
+            <Template Name="TaskRow" Params="task, i">
+                <Row>
+                    <Text>{task.description}</Text>
+                    <Button Message="Toggle(i)">Done</Button>
+                </Row>
+            </Template>
+            <Column>
+                <Use Template="TaskRow" task="{self.tasks[0]}" i="{0}" />
+                <Use Template="TaskRow" task="{last}" i="{self.tasks.len() - 1}" Padding="5" />
+            </Column>
+        </Component>"#;
+
+        let component = parse_str(raw_window).unwrap();
+
+        assert_eq!(component.templates.len(), 1);
+        assert_eq!(component.templates[0].params, ["task", "i"]);
+        let Node::Column(column) = component.content.unwrap().node else {
+            panic!("expected a column");
+        };
+        let [first, second] = &column.content[..] else {
+            panic!("expected two rows");
+        };
+        let texts_and_messages = |node: &ComponentNode| match &node.node {
+            Node::Row(row) => match (&row.content[0].node, &row.content[1].node) {
+                (Node::Text(text), Node::Button(button)) => {
+                    (text.content.clone(), button.message.clone())
+                }
+                _ => panic!("expected a text and a button"),
+            },
+            _ => panic!("expected a row"),
+        };
+        assert_eq!(
+            texts_and_messages(first),
+            (
+                "{(self.tasks[0]).description}".to_string(),
+                "Toggle(0)".to_string()
+            )
+        );
+        assert_eq!(
+            texts_and_messages(second),
+            (
+                "{last.description}".to_string(),
+                "Toggle((self.tasks.len() - 1))".to_string()
+            )
+        );
+        assert_eq!(second.layout.padding.value, 5.0);
+    }
+
+    #[test]
+    fn template_use_complains() {
+        let template = r#"<Template Name="TaskRow" Params="task"><Text>{task}</Text></Template>"#;
+        let error = |content: &str| {
+            parse_str(&format!("<Component>{template}{content}</Component>"))
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            error(r#"<Use Template="TaskRw" task="{t}" />"#),
+            "unknown template 'TaskRw', did you mean 'TaskRow'?"
+        );
+        assert_eq!(
+            error(r#"<Use Template="TaskRow" />"#),
+            "missing attribute 'task' on 'Use'"
+        );
+        assert_eq!(
+            error(r#"<Use Template="TaskRow" task="{t}" tsk="{t}" />"#),
+            "unknown attribute 'tsk' on 'Use', did you mean 'task'?"
+        );
+    }
+
+    #[test]
+    fn duplicate_template_complains() {
+        let raw_window = r#"<Component>
+  <Template Name="Row"><Text>First</Text></Template>
+  <Template Name="Row"><Text>Second</Text></Template>
+  <Use Template="Row" />
+</Component>"#;
+
+        let error = parse_str(raw_window).unwrap_err();
+
+        assert_eq!(error.to_string(), "template 'Row' is already defined");
+        assert_eq!(error.position(), Some(Position { line: 3, column: 3 }));
+
+        let parsed = parse_str_with(raw_window, &ParseOptions::lenient()).unwrap();
+        assert_eq!(parsed.warnings.len(), 1);
+        assert!(
+            matches!(parsed.component.content.unwrap().node, Node::Text(text) if text.content == "First")
+        );
+    }
+
+    #[test]
+    fn can_parse_props() {
+        let raw_window = r#"<Component>
+            <Props>
+                <Prop Name="label" Type="String" Default="Hello" />
+                <Prop Name="size" Type="f32" />
+            </Props>
+            <Text Content="{&amp;self.label}" />
+        </Component>"#;
+
+        let component = parse_str(raw_window).unwrap();
+
+        let props = component
+            .props
+            .iter()
+            .map(|prop| {
+                (
+                    prop.name.as_str(),
+                    prop.ty.as_str(),
+                    prop.default.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            props,
+            [("label", "String", Some("Hello")), ("size", "f32", None)]
+        );
+        assert!(matches!(component.content.unwrap().node, Node::Text(_)));
+        assert_eq!(
+            parse_str(r#"<Component><Props><Prop Name="label" /></Props></Component>"#)
+                .unwrap_err()
+                .to_string(),
+            "missing attribute 'Type' on 'Prop'"
+        );
+    }
+
+    #[test]
+    fn unknown_element_complains() {
+        let raw_window = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Component Message="Message">
+  <Column>
+    <Slider />
+  </Column>
+</Component>
+        "#;
+
+        let error = parse_str(raw_window).unwrap_err();
+
+        assert!(matches!(error, Error::UnknownElement { ref name, .. } if name == "Slider"));
+        assert_eq!(error.position(), Some(Position { line: 4, column: 5 }));
+    }
+
+    #[test]
+    fn unexpected_children_complain_or_warn() {
+        let cases = [
+            (
+                "<Match Value=\"{self}\">\n<Text>Hi</Text>\n</Match>",
+                "unexpected element 'Text' in 'Match', expected only 'MatchCase' elements",
+            ),
+            (
+                "<Match Value=\"{self}\"><MatchCase>\n<Text>A</Text>\n<Text>B</Text>\n</MatchCase></Match>",
+                "unexpected element 'Text' in 'MatchCase', expected a single element",
+            ),
+            (
+                "<Template Name=\"Pair\">\n<Text>A</Text>\n<Text>B</Text>\n</Template>",
+                "unexpected element 'Text' in 'Template', expected a single element",
+            ),
+            (
+                "<Text>A</Text>\n<Props />\n<Column />",
+                "unexpected element 'Column' in 'Component', expected a single element",
+            ),
+            (
+                "<Button Message=\"{Go}\">\n<Text>Go</Text>\n</Button>",
+                "unexpected element 'Text' in 'Button', expected only text",
+            ),
+            (
+                "<Text>\n<Button>Go</Button>\n</Text>",
+                "unexpected element 'Button' in 'Text', expected only text",
+            ),
+        ];
+
+        for (content, message) in cases {
+            let raw_window = format!("<Component>\n{content}\n</Component>");
+
+            let error = parse_str(&raw_window).unwrap_err();
+            assert!(matches!(error, Error::UnexpectedChild { .. }), "{error}");
+            assert_eq!(error.to_string(), message);
+
+            let parsed = parse_str_with(&raw_window, &ParseOptions::lenient()).unwrap();
+            let warnings = parsed
+                .warnings
+                .iter()
+                .map(|w| w.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(warnings, [message]);
+        }
+    }
+
+    #[test]
+    fn unexpected_root_complains() {
+        let error = parse_str("<Window><Text>Hello</Text></Window>").unwrap_err();
+
+        assert!(matches!(error, Error::UnexpectedRoot { ref name, .. } if name == "Window"));
+    }
+
+    #[test]
+    fn invalid_xml_complains() {
+        let error = parse_str("<Component>\n  <Text>\n</Component>").unwrap_err();
+
+        assert!(matches!(error, Error::Xml(_)));
+        assert_eq!(error.position().map(|p| p.line), Some(3));
+    }
 }
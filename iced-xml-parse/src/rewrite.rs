@@ -0,0 +1,576 @@
+//! Rewrites of the rust expressions and patterns in templates, done on syn syntax trees and
+//! spliced into the source to keep its formatting.
+
+use std::collections::HashMap;
+
+use proc_macro2::{Delimiter, Ident, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{
+    Arm, BinOp, Block, Expr, ExprClosure, ExprForLoop, ExprIf, ExprPath, ExprWhile, FieldValue,
+    Macro, Member, Pat, Path, Stmt, Token, Type,
+    parse::{Parse, Parser},
+    punctuated::Punctuated,
+    visit::{self, Visit},
+    visit_mut::{self, VisitMut},
+};
+
+/// The names bound by a pattern, e.g. `task` and `i` in `Some(Item { task, index: i })`.
+/// Destructuring patterns may be written in braces.
+pub(crate) fn pattern_bindings(pattern: &str) -> Vec<String> {
+    match Pat::parse_multi_with_leading_vert.parse_str(trim_braces(pattern)) {
+        Ok(pat) => bindings_of(&pat),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn bindings_of(pat: &Pat) -> Vec<String> {
+    struct Bindings(Vec<String>);
+
+    impl<'ast> Visit<'ast> for Bindings {
+        fn visit_pat_ident(&mut self, pat: &'ast syn::PatIdent) {
+            // `None` is parsed as a binding, like rustc does before resolving names
+            let name = pat.ident.to_string();
+            if name.starts_with(|c: char| c.is_lowercase() || c == '_') {
+                self.0.push(name);
+            }
+            visit::visit_pat_ident(self, pat);
+        }
+
+        fn visit_type(&mut self, _: &'ast Type) {}
+    }
+
+    let mut bindings = Bindings(Vec::new());
+    bindings.visit_pat(pat);
+    bindings.0
+}
+
+fn trim_braces(text: &str) -> &str {
+    text.strip_prefix('{')
+        .and_then(|text| text.strip_suffix('}'))
+        .unwrap_or(text)
+}
+
+/// Replaces the identifiers naming a parameter in an expression, leaving fields, paths, string
+/// literals and names bound by closures, `let` and patterns alone. Struct shorthand keeps its
+/// field, e.g. `Foo { task }` becomes `Foo { task: t }`.
+pub(crate) fn substitute_params(expr: &str, args: &HashMap<&str, String>) -> String {
+    let none = HashMap::new();
+    let substitute = Substitute::new(args, &none);
+    substitute_with(expr, Expr::parse, substitute, |substitute, expr| {
+        substitute.visit_expr_mut(expr)
+    })
+}
+
+/// Replaces the fields of `self` named in `values` by their value, e.g. `{self.count + 1}`
+/// becomes `{5 + 1}` for a `count` of `5`. Methods of `self` and fields of other values are
+/// kept.
+pub fn substitute_self_fields(expr: &str, values: &HashMap<&str, String>) -> String {
+    let none = HashMap::new();
+    let substitute = Substitute::new(&none, values);
+    substitute_with(expr, Expr::parse, substitute, |substitute, expr| {
+        substitute.visit_expr_mut(expr)
+    })
+}
+
+/// The field of `self` an expression in braces is, possibly borrowed, e.g. `label` for
+/// `{&self.label}`.
+pub fn self_field(expr: &str) -> Option<String> {
+    let mut expr = syn::parse_str::<Expr>(trim_braces(expr)).ok()?;
+    loop {
+        expr = match expr {
+            Expr::Reference(reference) => *reference.expr,
+            Expr::Paren(paren) => *paren.expr,
+            Expr::Field(field) if is_self(&field.base) => {
+                return match field.member {
+                    Member::Named(name) => Some(name.to_string()),
+                    Member::Unnamed(_) => None,
+                };
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn is_self(expr: &Expr) -> bool {
+    matches!(expr, Expr::Path(path) if path.qself.is_none() && path.path.is_ident("self"))
+}
+
+/// Replaces the parameters in the paths of a pattern, e.g. `kinds::Some`, which may be written
+/// in braces.
+pub(crate) fn substitute_pattern(pattern: &str, args: &HashMap<&str, String>) -> String {
+    let inner = trim_braces(pattern);
+    let start = usize::from(inner.len() != pattern.len());
+    let none = HashMap::new();
+    let substitute = Substitute::new(args, &none);
+    let substituted = substitute_with(
+        inner,
+        Pat::parse_multi_with_leading_vert,
+        substitute,
+        |substitute, pat| substitute.visit_pat_mut(pat),
+    );
+    format!(
+        "{}{substituted}{}",
+        &pattern[..start],
+        &pattern[start + inner.len()..]
+    )
+}
+
+/// Marks the tokens to replace in the syntax tree, the `n`th standing for the `n`th
+/// replacement.
+const MARKER: &str = "__ixml_param_";
+
+/// Parses `source` with syn, lets `visit` mark the tokens to replace, and replaces them in the
+/// source to keep its formatting. Sources which do not parse only have their parameters
+/// replaced, for the code generator to report them.
+fn substitute_with<T: ToTokens>(
+    source: &str,
+    parser: impl Parser<Output = T>,
+    mut substitute: Substitute,
+    visit: impl FnOnce(&mut Substitute, &mut T),
+) -> String {
+    let Ok(tokens) = source.parse::<TokenStream>() else {
+        return source.to_string();
+    };
+    let original = leaves(tokens.clone());
+    let Ok(mut tree) = parser.parse2(tokens) else {
+        return substitute_leaves(source, &original, substitute.params);
+    };
+    visit(&mut substitute, &mut tree);
+    if substitute.replacements.is_empty() {
+        return source.to_string();
+    }
+
+    let replacement = |leaf: &str| {
+        let index = leaf.strip_prefix(MARKER)?.parse::<usize>().ok()?;
+        substitute.replacements.get(index)
+    };
+    let substituted = leaves(tree.into_token_stream());
+    match offsets(source, &original) {
+        Some(offsets) if original.len() == substituted.len() => {
+            let mut out = String::with_capacity(source.len());
+            let mut end = 0;
+            for (i, new) in substituted.iter().enumerate() {
+                if let Some((replacement, len)) = replacement(new) {
+                    let last = i + len - 1;
+                    out.push_str(&source[end..offsets[i]]);
+                    out.push_str(replacement);
+                    end = offsets[last] + original[last].len();
+                }
+            }
+            out.push_str(&source[end..]);
+            out
+        }
+        // Only when syn prints the tree differently, e.g. doc comments as attributes, at the
+        // cost of the formatting
+        _ => {
+            let mut out = Vec::new();
+            let mut leaves = substituted.iter();
+            while let Some(leaf) = leaves.next() {
+                match replacement(leaf) {
+                    Some((replacement, len)) => {
+                        out.push(replacement.as_str());
+                        leaves.nth(len - 2);
+                    }
+                    None => out.push(leaf),
+                }
+            }
+            out.join(" ")
+        }
+    }
+}
+
+/// Replaces the identifiers naming a parameter in a source which lexes but does not parse,
+/// unless they are fields, paths or labels, for the code generator to report the error with the
+/// arguments.
+fn substitute_leaves(source: &str, leaves: &[String], args: &HashMap<&str, String>) -> String {
+    let Some(offsets) = offsets(source, leaves) else {
+        return source.to_string();
+    };
+    let mut out = String::with_capacity(source.len());
+    let mut end = 0;
+    for (i, (offset, leaf)) in offsets.into_iter().zip(leaves).enumerate() {
+        let before = |n: usize| i.checked_sub(n).map(|i| leaves[i].as_str());
+        let is_member = matches!(before(1), Some("." | "'"))
+            || before(1) == Some(":") && before(2) == Some(":");
+        let arg = args.get(leaf.as_str()).filter(|_| !is_member);
+        if let Some(arg) = arg.filter(|_| syn::parse_str::<Ident>(leaf).is_ok()) {
+            out.push_str(&source[end..offset]);
+            out.push_str(arg);
+            end = offset + leaf.len();
+        }
+    }
+    out.push_str(&source[end..]);
+    out
+}
+
+/// The text of the tokens of a stream, with the delimiters of groups.
+fn leaves(tokens: TokenStream) -> Vec<String> {
+    let mut leaves = Vec::new();
+    for token in tokens {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                if !open.is_empty() {
+                    leaves.push(open.to_string());
+                }
+                leaves.extend(self::leaves(group.stream()));
+                if !close.is_empty() {
+                    leaves.push(close.to_string());
+                }
+            }
+            token => leaves.push(token.to_string()),
+        }
+    }
+    leaves
+}
+
+/// Where each of the tokens lexed from `source` starts, skipping whitespace and comments.
+fn offsets(source: &str, leaves: &[String]) -> Option<Vec<usize>> {
+    let mut offsets = Vec::with_capacity(leaves.len());
+    let mut pos = 0;
+    for leaf in leaves {
+        pos = skip_trivia(source, pos);
+        if !source[pos..].starts_with(leaf.as_str()) {
+            return None;
+        }
+        offsets.push(pos);
+        pos += leaf.len();
+    }
+    Some(offsets)
+}
+
+fn skip_trivia(source: &str, mut pos: usize) -> usize {
+    loop {
+        let rest = &source[pos..];
+        let trimmed = rest.trim_start();
+        pos += rest.len() - trimmed.len();
+        if trimmed.starts_with("//") {
+            pos += trimmed.find('\n').unwrap_or(trimmed.len());
+        } else if trimmed.starts_with("/*") {
+            let mut depth = 0usize;
+            let mut chars = trimmed.char_indices().peekable();
+            let mut len = trimmed.len();
+            while let Some((i, c)) = chars.next() {
+                match (c, chars.peek().map(|(_, c)| *c)) {
+                    ('/', Some('*')) => {
+                        depth += 1;
+                        chars.next();
+                    }
+                    ('*', Some('/')) => {
+                        depth -= 1;
+                        chars.next();
+                        if depth == 0 {
+                            len = i + 2;
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            pos += len;
+        } else {
+            return pos;
+        }
+    }
+}
+
+/// Marks the identifiers naming a parameter and the fields of `self` to replace, keeping
+/// track of the names bound around them.
+struct Substitute<'a> {
+    params: &'a HashMap<&'a str, String>,
+    self_fields: &'a HashMap<&'a str, String>,
+    /// The names bound by each closure, block, arm or `if let` the visitor is in
+    scopes: Vec<Vec<String>>,
+    /// The text replacing each marker, with the number of tokens it replaces
+    replacements: Vec<(String, usize)>,
+}
+
+impl<'a> Substitute<'a> {
+    fn new(
+        params: &'a HashMap<&'a str, String>,
+        self_fields: &'a HashMap<&'a str, String>,
+    ) -> Self {
+        Self {
+            params,
+            self_fields,
+            scopes: Vec::new(),
+            replacements: Vec::new(),
+        }
+    }
+
+    fn arg(&self, ident: &Ident) -> Option<String> {
+        let name = ident.to_string();
+        if self.scopes.iter().flatten().any(|bound| *bound == name) {
+            return None;
+        }
+        self.params.get(name.as_str()).cloned()
+    }
+
+    /// Replaces `ident` and the `len - 1` tokens after it.
+    fn mark(&mut self, ident: &mut Ident, replacement: String, len: usize) {
+        let marker = format!("{MARKER}{}", self.replacements.len());
+        *ident = Ident::new(&marker, ident.span());
+        self.replacements.push((replacement, len));
+    }
+
+    fn scoped(&mut self, names: Vec<String>, visit: impl FnOnce(&mut Self)) {
+        self.scopes.push(names);
+        visit(self);
+        self.scopes.pop();
+    }
+}
+
+/// The names bound by the `let`s of an `if` or `while` condition, e.g. `if let Some(a) = b && c`.
+fn condition_bindings(cond: &Expr) -> Vec<String> {
+    match cond {
+        Expr::Let(let_expr) => bindings_of(&let_expr.pat),
+        Expr::Binary(binary) if matches!(binary.op, BinOp::And(_)) => {
+            let mut names = condition_bindings(&binary.left);
+            names.extend(condition_bindings(&binary.right));
+            names
+        }
+        _ => Vec::new(),
+    }
+}
+
+impl VisitMut for Substitute<'_> {
+    /// Replaces `self.field`, the three tokens starting at `self`.
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Expr::Field(field) = expr
+            && is_self(&field.base)
+            && let Member::Named(name) = &field.member
+            && let Some(value) = self.self_fields.get(name.to_string().as_str()).cloned()
+            && let Expr::Path(base) = &mut *field.base
+        {
+            self.mark(&mut base.path.segments[0].ident, value, 3);
+            return;
+        }
+        visit_mut::visit_expr_mut(self, expr);
+    }
+
+    /// Replaces the first segment of paths, e.g. `task` or `kinds` in `kinds::Some`.
+    fn visit_path_mut(&mut self, path: &mut Path) {
+        if path.leading_colon.is_none()
+            && let Some(first) = path.segments.first_mut()
+            && let Some(arg) = self.arg(&first.ident)
+        {
+            self.mark(&mut first.ident, arg, 1);
+        }
+        visit_mut::visit_path_mut(self, path);
+    }
+
+    fn visit_expr_path_mut(&mut self, expr: &mut ExprPath) {
+        // `<T as Trait>::name` names an item
+        if expr.qself.is_none() {
+            self.visit_path_mut(&mut expr.path);
+        }
+    }
+
+    /// Types never name a parameter.
+    fn visit_type_mut(&mut self, _: &mut Type) {}
+
+    /// Substitutes the arguments of macros written like calls, e.g. `format!("{}", task)`.
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        let parser = Punctuated::<Expr, Token![,]>::parse_terminated;
+        if let Ok(mut args) = parser.parse2(mac.tokens.clone()) {
+            args.iter_mut().for_each(|arg| self.visit_expr_mut(arg));
+            mac.tokens = args.into_token_stream();
+        }
+    }
+
+    fn visit_field_value_mut(&mut self, field: &mut FieldValue) {
+        match (&mut field.member, field.colon_token) {
+            // Shorthand is printed as its member
+            (Member::Named(ident), None) => {
+                if let Some(arg) = self.arg(ident) {
+                    let replacement = format!("{ident}: {arg}");
+                    self.mark(ident, replacement, 1);
+                }
+            }
+            _ => self.visit_expr_mut(&mut field.expr),
+        }
+    }
+
+    fn visit_expr_closure_mut(&mut self, closure: &mut ExprClosure) {
+        let names = closure.inputs.iter().flat_map(bindings_of).collect();
+        self.scoped(names, |this| this.visit_expr_mut(&mut closure.body));
+    }
+
+    fn visit_arm_mut(&mut self, arm: &mut Arm) {
+        self.visit_pat_mut(&mut arm.pat);
+        self.scoped(bindings_of(&arm.pat), |this| {
+            if let Some((_, guard)) = &mut arm.guard {
+                this.visit_expr_mut(guard);
+            }
+            this.visit_expr_mut(&mut arm.body);
+        });
+    }
+
+    fn visit_block_mut(&mut self, block: &mut Block) {
+        self.scoped(Vec::new(), |this| {
+            for stmt in &mut block.stmts {
+                match stmt {
+                    Stmt::Local(local) => {
+                        if let Some(init) = &mut local.init {
+                            this.visit_local_init_mut(init);
+                        }
+                        this.visit_pat_mut(&mut local.pat);
+                        let names = bindings_of(&local.pat);
+                        this.scopes.last_mut().expect("in a scope").extend(names);
+                    }
+                    stmt => this.visit_stmt_mut(stmt),
+                }
+            }
+        });
+    }
+
+    fn visit_expr_if_mut(&mut self, expr: &mut ExprIf) {
+        self.visit_expr_mut(&mut expr.cond);
+        self.scoped(condition_bindings(&expr.cond), |this| {
+            this.visit_block_mut(&mut expr.then_branch)
+        });
+        if let Some((_, else_branch)) = &mut expr.else_branch {
+            self.visit_expr_mut(else_branch);
+        }
+    }
+
+    fn visit_expr_while_mut(&mut self, expr: &mut ExprWhile) {
+        self.visit_expr_mut(&mut expr.cond);
+        self.scoped(condition_bindings(&expr.cond), |this| {
+            this.visit_block_mut(&mut expr.body)
+        });
+    }
+
+    fn visit_expr_for_loop_mut(&mut self, expr: &mut ExprForLoop) {
+        self.visit_expr_mut(&mut expr.expr);
+        self.visit_pat_mut(&mut expr.pat);
+        self.scoped(bindings_of(&expr.pat), |this| {
+            this.visit_block_mut(&mut expr.body)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn substituted(expr: &str) -> String {
+        let args = HashMap::from([("task", "t".to_string()), ("i", "(i + 1)".to_string())]);
+        substitute_params(expr, &args)
+    }
+
+    #[test]
+    fn substitutes_parameters() {
+        assert_eq!(substituted("{task.description}"), "{t.description}");
+        assert_eq!(substituted("Toggle(i)"), "Toggle((i + 1))");
+        assert_eq!(
+            substituted("{format!(\"{}\", task)}"),
+            "{format!(\"{}\", t)}"
+        );
+    }
+
+    #[test]
+    fn keeps_fields_paths_and_strings() {
+        assert_eq!(substituted("{self.task.i}"), "{self.task.i}");
+        assert_eq!(substituted("{Message::task}"), "{Message::task}");
+        assert_eq!(substituted("{\"task \\\" i\"}"), "{\"task \\\" i\"}");
+        assert_eq!(substituted("{tasks[index]}"), "{tasks[index]}");
+    }
+
+    #[test]
+    fn keeps_struct_fields() {
+        assert_eq!(substituted("{Row { task }}"), "{Row { task: t }}");
+        assert_eq!(
+            substituted("Edit(Task { i, task: task })"),
+            "Edit(Task { i: (i + 1), task: t })"
+        );
+        assert_eq!(substituted("{ task }"), "{ t }");
+    }
+
+    #[test]
+    fn closure_parameters_shadow_parameters() {
+        assert_eq!(
+            substituted("{task.items.iter().map(|task| task.len()).sum::<usize>() + task.len()}"),
+            "{t.items.iter().map(|task| task.len()).sum::<usize>() + t.len()}"
+        );
+        assert_eq!(
+            substituted("{f(|(i, _): (usize, u8)| i, i)}"),
+            "{f(|(i, _): (usize, u8)| i, (i + 1))}"
+        );
+        assert_eq!(substituted("{task | i}"), "{t | (i + 1)}");
+        assert_eq!(substituted("{f(|| task)}"), "{f(|| t)}");
+    }
+
+    #[test]
+    fn keeps_chars_lifetimes_and_comments() {
+        assert_eq!(
+            substituted("{if c == '}' { task } else { i }}"),
+            "{if c == '}' { t } else { (i + 1) }}"
+        );
+        assert_eq!(
+            substituted("{'task: loop { break 'task task /* i */; }}"),
+            "{'task: loop { break 'task t /* i */; }}"
+        );
+        assert_eq!(
+            substituted("{r#\"task\"#.len() + i}"),
+            "{r#\"task\"#.len() + (i + 1)}"
+        );
+    }
+
+    #[test]
+    fn bindings_shadow_parameters() {
+        assert_eq!(
+            substituted("{match task { Some(task) => task.len(), None => i }}"),
+            "{match t { Some(task) => task.len(), None => (i + 1) }}"
+        );
+        assert_eq!(
+            substituted("{ let i = task.len(); i }"),
+            "{ let i = t.len(); i }"
+        );
+        assert_eq!(
+            substituted("{if let Some(task) = task.next() { task } else { i }}"),
+            "{if let Some(task) = t.next() { task } else { (i + 1) }}"
+        );
+        assert_eq!(
+            substituted("{ <task as Trait>::i(task) }"),
+            "{ <task as Trait>::i(t) }"
+        );
+    }
+
+    #[test]
+    fn invalid_expression_is_substituted_by_token() {
+        assert_eq!(substituted("{task.i +}"), "{t.i +}");
+        assert_eq!(substituted("{task.(}"), "{task.(}");
+    }
+
+    #[test]
+    fn finds_pattern_bindings() {
+        assert_eq!(
+            pattern_bindings("Some(Item { task, index: i, kind: Kind::Done, .. })"),
+            ["task", "i"]
+        );
+        assert_eq!(pattern_bindings("{(ref a, mut b, _)}"), ["a", "b"]);
+        assert!(pattern_bindings("None").is_empty());
+    }
+
+    #[test]
+    fn substitutes_fields_of_self() {
+        let values = HashMap::from([("count", "5".to_string())]);
+
+        assert_eq!(
+            substitute_self_fields("{self.count  +  self.count()}", &values),
+            "{5  +  self.count()}"
+        );
+        assert_eq!(
+            substitute_self_fields("{other.count + \"self.count\"}", &values),
+            "{other.count + \"self.count\"}"
+        );
+        assert_eq!(self_field("{&(self.count)}").as_deref(), Some("count"));
+        assert_eq!(self_field("{self.count + 1}"), None);
+    }
+}
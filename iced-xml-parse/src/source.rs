@@ -0,0 +1,113 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+/// Loads templates by path, so that they can be read from files, memory or custom loaders,
+/// e.g. an archive. Any closure taking a path and returning the bytes is a source too.
+pub trait TemplateSource {
+    /// The raw bytes of a template, which the parser decodes.
+    fn load(&self, path: &Path) -> io::Result<Vec<u8>>;
+}
+
+/// Reads templates from the file system.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FileSource;
+
+impl TemplateSource for FileSource {
+    fn load(&self, path: &Path) -> io::Result<Vec<u8>> {
+        std::fs::read(path)
+    }
+}
+
+/// Templates kept in memory, e.g. embedded with `include_bytes!`.
+#[derive(Debug, Default, Clone)]
+pub struct MemorySource {
+    templates: HashMap<PathBuf, Vec<u8>>,
+}
+
+impl MemorySource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, path: impl Into<PathBuf>, template: impl Into<Vec<u8>>) -> Self {
+        self.insert(path, template);
+        self
+    }
+
+    pub fn insert(&mut self, path: impl Into<PathBuf>, template: impl Into<Vec<u8>>) {
+        self.templates.insert(path.into(), template.into());
+    }
+}
+
+impl TemplateSource for MemorySource {
+    fn load(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.templates.get(path).cloned().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no template '{}'", path.display()),
+            )
+        })
+    }
+}
+
+impl<F> TemplateSource for F
+where
+    F: Fn(&Path) -> io::Result<Vec<u8>>,
+{
+    fn load(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self(path)
+    }
+}
+
+/// Resolves the `Source` of an include relative to the directory of the including template.
+/// `..` and `.` are removed, so that a template reached by different paths is recognised.
+pub(crate) fn resolve(including: Option<&Path>, source: impl AsRef<Path>) -> PathBuf {
+    let path = match including.and_then(Path::parent) {
+        Some(dir) => dir.join(source),
+        None => source.as_ref().to_path_buf(),
+    };
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    resolved.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_relative_to_including_template() {
+        assert_eq!(
+            resolve(Some(Path::new("ui/main.ixml")), "header.ixml"),
+            PathBuf::from("ui/header.ixml")
+        );
+        assert_eq!(
+            resolve(
+                Some(Path::new("ui/pages/main.ixml")),
+                "./../parts/../header.ixml"
+            ),
+            PathBuf::from("ui/header.ixml")
+        );
+        assert_eq!(
+            resolve(Some(Path::new("main.ixml")), "../header.ixml"),
+            PathBuf::from("../header.ixml")
+        );
+        assert_eq!(resolve(None, "header.ixml"), PathBuf::from("header.ixml"));
+    }
+}
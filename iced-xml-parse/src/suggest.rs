@@ -0,0 +1,57 @@
+/// The candidate most similar to a misspelled `name`, if any is similar enough.
+pub fn did_you_mean<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    candidates
+        .into_iter()
+        .map(|c| (c, edit_distance(name, c)))
+        .filter(|(_, distance)| *distance <= (name.len() / 3).max(1))
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate)
+}
+
+/// The edit distance between two strings, counting swapped neighbours as a single edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    (0..=a.len()).for_each(|i| d[i][0] = i);
+    (0..=b.len()).for_each(|j| d[0][j] = j);
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_compute_edit_distance() {
+        assert_eq!(edit_distance("value", "value"), 0);
+        assert_eq!(edit_distance("valeu", "value"), 1);
+        assert_eq!(edit_distance("count", "counter"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn suggests_most_similar_candidate() {
+        let candidates = ["Message", "Content", "Size"];
+
+        assert_eq!(did_you_mean("Sise", candidates), Some("Size"));
+        assert_eq!(did_you_mean("Mesage", candidates), Some("Message"));
+        assert_eq!(did_you_mean("Width", candidates), None);
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use iced_xml_core::{ComponentNode, MatchCaseNode, Node, TemplateNode};
+
+use crate::rewrite::{pattern_bindings, substitute_params, substitute_pattern};
+
+/// Expands a template with the arguments of a `Use` element, given as written: expressions in
+/// braces, or text which is passed as a string literal.
+///
+/// Parameters are replaced in the expressions of the template, e.g. `{task.description}` with
+/// `task="{tasks[0]}"` becomes `{(tasks[0]).description}`.
+pub(crate) fn expand(template: &TemplateNode, args: &HashMap<&str, &str>) -> Option<ComponentNode> {
+    let args = args
+        .iter()
+        .map(|(param, value)| (*param, argument(value)))
+        .collect::<HashMap<_, _>>();
+    let mut content = *template.content.clone()?;
+    substitute_node(&mut content, &args);
+    Some(content)
+}
+
+/// The expression an argument stands for in the template.
+fn argument(value: &str) -> String {
+    match value.strip_prefix('{').and_then(|v| v.strip_suffix('}')) {
+        Some(expr) if is_path(expr.trim()) => expr.trim().to_string(),
+        Some(expr) => format!("({})", expr.trim()),
+        None => format!("{value:?}"),
+    }
+}
+
+/// Whether the expression needs no parentheses, e.g. `task` or `self.tasks`.
+fn is_path(expr: &str) -> bool {
+    !expr.is_empty()
+        && expr
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | ':'))
+}
+
+fn substitute_node(node: &mut ComponentNode, args: &HashMap<&str, String>) {
+    match &mut node.node {
+        Node::Button(button) => {
+            substitute_expr(&mut button.content, args);
+            // Messages are expressions with or without braces
+            button.message = substitute_params(&button.message, args);
+        }
+        Node::Text(text) => substitute_expr(&mut text.content, args),
+        Node::Column(column) => column
+            .content
+            .iter_mut()
+            .for_each(|c| substitute_node(c, args)),
+        Node::Row(row) => row
+            .content
+            .iter_mut()
+            .for_each(|c| substitute_node(c, args)),
+        Node::Match(match_node) => {
+            substitute_expr(&mut match_node.value, args);
+            for case in &mut match_node.cases {
+                substitute_case(case, args);
+            }
+        }
+        Node::Placeholder(_) => {}
+    }
+}
+
+/// Substitutes the pattern of a case and its content, in which the names bound by the pattern
+/// shadow the parameters.
+fn substitute_case(case: &mut MatchCaseNode, args: &HashMap<&str, String>) {
+    let mut bound = pattern_bindings(&case.case);
+    if let Some(destructure) = &case.destructure {
+        bound.extend(pattern_bindings(destructure));
+    }
+    let args = args
+        .iter()
+        .filter(|(param, _)| !bound.iter().any(|name| name == *param))
+        .map(|(param, arg)| (*param, arg.clone()))
+        .collect::<HashMap<_, _>>();
+
+    case.case = substitute_pattern(&case.case, &args);
+    if let Some(destructure) = &mut case.destructure {
+        *destructure = substitute_pattern(destructure, &args);
+    }
+    if let Some(content) = &mut case.content {
+        substitute_node(content, &args);
+    }
+}
+
+/// Substitutes the parameters of a value if it is an expression in braces.
+fn substitute_expr(value: &mut String, args: &HashMap<&str, String>) {
+    if value.starts_with('{') && value.ends_with('}') {
+        *value = substitute_params(value, args);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn case_bindings_shadow_parameters() {
+        let args = HashMap::from([
+            ("task", "t".to_string()),
+            ("kinds", "my::kinds".to_string()),
+        ]);
+        let mut case = MatchCaseNode {
+            case: "kinds::Some".to_string(),
+            destructure: Some("{(task)}".to_string()),
+            content: Some(Box::new(ComponentNode {
+                node: Node::Text(iced_xml_core::TextNode {
+                    content: "{task}".to_string(),
+                    size: 10.0,
+                    color: None,
+                    font: None,
+                }),
+                layout: Default::default(),
+                spans: Default::default(),
+            })),
+            spans: Default::default(),
+        };
+
+        substitute_case(&mut case, &args);
+
+        assert_eq!(case.case, "my::kinds::Some");
+        assert_eq!(case.destructure.as_deref(), Some("{(task)}"));
+        let Node::Text(text) = &case.content.unwrap().node else {
+            unreachable!();
+        };
+        assert_eq!(text.content, "{task}");
+    }
+
+    #[test]
+    fn arguments_are_expressions_or_strings() {
+        assert_eq!(argument("{t}"), "t");
+        assert_eq!(argument("{self.tasks}"), "self.tasks");
+        assert_eq!(argument("{tasks[0]}"), "(tasks[0])");
+        assert_eq!(argument("Hello \"you\""), "\"Hello \\\"you\\\"\"");
+    }
+}
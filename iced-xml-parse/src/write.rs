@@ -0,0 +1,523 @@
+use iced_xml_core::{
+    Component, ComponentNode, MatchCaseNode, Node, NodeLayout, PropNode, TemplateNode,
+};
+
+const INDENT: &str = "  ";
+const DEFAULT_TEXT_SIZE: f32 = 10.0;
+
+/// Writes a component as a canonical template, which parses back into the same tree.
+///
+/// Elements are indented by two spaces and attributes written in a fixed order: the node's own
+/// attributes first, then `HorizontalAlignment`, `VerticalAlignment`, `Padding`, `Width` and
+/// `Height`. Attributes
+/// with their default value are left out, text content is written inline.
+pub fn to_ixml(component: &Component) -> String {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let attributes = [
+        ("Model", component.model_type.as_deref()),
+        ("Message", component.message_type.as_deref()),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| value.map(|value| attribute(name, value)))
+    .collect::<String>();
+
+    write_element("Component", &attributes, 0, &mut out, |out| {
+        if !component.props.is_empty() {
+            write_element("Props", "", 1, out, |out| {
+                component
+                    .props
+                    .iter()
+                    .for_each(|prop| write_prop(prop, 2, out))
+            });
+        }
+        component
+            .templates
+            .iter()
+            .for_each(|template| write_template(template, 1, out));
+        if let Some(content) = &component.content {
+            write_node(content, 1, out);
+        }
+    });
+    out
+}
+
+/// Writes a single node and its children, e.g. to paste into a template.
+pub fn node_to_ixml(node: &ComponentNode) -> String {
+    let mut out = String::new();
+    write_node(node, 0, &mut out);
+    out
+}
+
+fn write_node(node: &ComponentNode, depth: usize, out: &mut String) {
+    let layout = layout_attributes(&node.layout);
+    match &node.node {
+        Node::Button(button) => {
+            let message = optional_attribute("Message", &button.message);
+            write_text_element(
+                "Button",
+                &format!("{message}{layout}"),
+                &button.content,
+                depth,
+                out,
+            );
+        }
+        Node::Text(text) => {
+            let size = if text.size == DEFAULT_TEXT_SIZE {
+                String::new()
+            } else {
+                attribute("Size", &text.size.to_string())
+            };
+            let color = text
+                .color
+                .map(|color| attribute("Color", &color.to_string()))
+                .unwrap_or_default();
+            let font = text
+                .font
+                .as_ref()
+                .map(|font| attribute("Font", &font.to_string()))
+                .unwrap_or_default();
+            write_text_element(
+                "Text",
+                &format!("{size}{color}{font}{layout}"),
+                &text.content,
+                depth,
+                out,
+            );
+        }
+        Node::Column(column) => {
+            write_element("Column", &layout, depth, out, |out| {
+                column
+                    .content
+                    .iter()
+                    .for_each(|c| write_node(c, depth + 1, out))
+            });
+        }
+        Node::Row(row) => {
+            write_element("Row", &layout, depth, out, |out| {
+                row.content
+                    .iter()
+                    .for_each(|c| write_node(c, depth + 1, out))
+            });
+        }
+        Node::Match(match_node) => {
+            let value = optional_attribute("Value", &match_node.value);
+            write_element("Match", &format!("{value}{layout}"), depth, out, |out| {
+                match_node
+                    .cases
+                    .iter()
+                    .for_each(|case| write_match_case(case, depth + 1, out))
+            });
+        }
+        Node::Placeholder(placeholder) => {
+            write_element(&placeholder.name, &layout, depth, out, |_| {});
+        }
+    }
+}
+
+fn write_match_case(case: &MatchCaseNode, depth: usize, out: &mut String) {
+    let mut attributes = attribute("Case", &case.case);
+    if let Some(destructure) = &case.destructure {
+        attributes.push_str(&attribute("Destructure", destructure));
+    }
+    write_element("MatchCase", &attributes, depth, out, |out| {
+        if let Some(content) = &case.content {
+            write_node(content, depth + 1, out);
+        }
+    });
+}
+
+fn write_prop(prop: &PropNode, depth: usize, out: &mut String) {
+    let mut attributes = attribute("Name", &prop.name) + &attribute("Type", &prop.ty);
+    if let Some(default) = &prop.default {
+        attributes.push_str(&attribute("Default", default));
+    }
+    write_element("Prop", &attributes, depth, out, |_| {});
+}
+
+fn write_template(template: &TemplateNode, depth: usize, out: &mut String) {
+    let mut attributes = attribute("Name", &template.name);
+    if !template.params.is_empty() {
+        attributes.push_str(&attribute("Params", &template.params.join(", ")));
+    }
+    write_element("Template", &attributes, depth, out, |out| {
+        if let Some(content) = &template.content {
+            write_node(content, depth + 1, out);
+        }
+    });
+}
+
+/// Writes an element whose children are written by `children`, self-closed if there are none.
+fn write_element(
+    name: &str,
+    attributes: &str,
+    depth: usize,
+    out: &mut String,
+    children: impl FnOnce(&mut String),
+) {
+    let indent = INDENT.repeat(depth);
+    let mut content = String::new();
+    children(&mut content);
+    if content.is_empty() {
+        out.push_str(&format!("{indent}<{name}{attributes} />\n"));
+    } else {
+        out.push_str(&format!(
+            "{indent}<{name}{attributes}>\n{content}{indent}</{name}>\n"
+        ));
+    }
+}
+
+/// Writes text inline, unless the parser would trim it, in which case it is written as the
+/// `Content` attribute.
+fn write_text_element(name: &str, attributes: &str, text: &str, depth: usize, out: &mut String) {
+    let indent = INDENT.repeat(depth);
+    if text.trim() != text {
+        let content = attribute("Content", text);
+        out.push_str(&format!("{indent}<{name}{content}{attributes} />\n"));
+    } else if text.is_empty() {
+        out.push_str(&format!("{indent}<{name}{attributes} />\n"));
+    } else {
+        let text = escape_text(text);
+        out.push_str(&format!("{indent}<{name}{attributes}>{text}</{name}>\n"));
+    }
+}
+
+fn layout_attributes(layout: &NodeLayout) -> String {
+    let default = NodeLayout::default();
+    let mut attributes = String::new();
+    if layout.align_x != default.align_x {
+        attributes.push_str(&attribute(
+            "HorizontalAlignment",
+            &layout.align_x.to_string(),
+        ));
+    }
+    if layout.align_y != default.align_y {
+        attributes.push_str(&attribute("VerticalAlignment", &layout.align_y.to_string()));
+    }
+    if layout.padding != default.padding {
+        attributes.push_str(&attribute("Padding", &layout.padding.value.to_string()));
+    }
+    if layout.width != default.width {
+        attributes.push_str(&attribute("Width", &layout.width.to_string()));
+    }
+    if layout.height != default.height {
+        attributes.push_str(&attribute("Height", &layout.height.to_string()));
+    }
+    attributes
+}
+
+fn attribute(name: &str, value: &str) -> String {
+    format!(" {name}=\"{}\"", escape_attribute(value))
+}
+
+/// An attribute the parser reads as empty when missing, left out when empty.
+fn optional_attribute(name: &str, value: &str) -> String {
+    if value.is_empty() {
+        String::new()
+    } else {
+        attribute(name, value)
+    }
+}
+
+/// Escapes an attribute value, using character references for the whitespace xml would
+/// otherwise normalize to spaces.
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+        .replace('\r', "&#13;")
+        .replace('\t', "&#9;")
+}
+
+/// Escapes text content, keeping `\r` which xml would otherwise normalize to `\n`.
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\r', "&#13;")
+}
+
+#[cfg(test)]
+mod tests {
+    use iced_xml_core::{
+        ButtonNode, Color, ColumnNode, Font, HorizontalAlignment, Length, MatchNode, Padding,
+        RowNode, Spans, TextNode, VerticalAlignment,
+    };
+    use rand::{Rng, SeedableRng, rngs::StdRng, seq::IndexedRandom};
+
+    use super::*;
+    use crate::parse_str;
+
+    #[test]
+    fn can_write_component() {
+        let component = parse_str(
+            r#"<Component Message="Message" Model="Counter">
+  <Column Padding="20" HorizontalAlignment="center"><Button Message="{Message::Increment}">
+  Increment</Button>
+    <Text Size="50" Content="{&amp;self.value}" />
+    <Text Size="10" VerticalAlignment="Bottom">a &lt; b</Text>
+  </Column>
+</Component>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            to_ixml(&component),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<Component Model="Counter" Message="Message">
+  <Column HorizontalAlignment="Center" Padding="20">
+    <Button Message="{Message::Increment}">Increment</Button>
+    <Text Size="50">{&amp;self.value}</Text>
+    <Text VerticalAlignment="Bottom">a &lt; b</Text>
+  </Column>
+</Component>
+"#
+        );
+    }
+
+    #[test]
+    fn can_write_match_node() {
+        let node = ComponentNode {
+            node: Node::Match(MatchNode {
+                value: "{self}".to_string(),
+                cases: vec![
+                    MatchCaseNode {
+                        case: "Loaded".to_string(),
+                        destructure: Some("{State { value, .. }}".to_string()),
+                        content: Some(Box::new(text("{value}"))),
+                        spans: Spans::default(),
+                    },
+                    MatchCaseNode {
+                        case: "_".to_string(),
+                        destructure: None,
+                        content: None,
+                        spans: Spans::default(),
+                    },
+                ],
+            }),
+            layout: NodeLayout::default(),
+            spans: Spans::default(),
+        };
+
+        assert_eq!(
+            node_to_ixml(&node),
+            r#"<Match Value="{self}">
+  <MatchCase Case="Loaded" Destructure="{State { value, .. }}">
+    <Text>{value}</Text>
+  </MatchCase>
+  <MatchCase Case="_" />
+</Match>
+"#
+        );
+    }
+
+    #[test]
+    fn attributes_keep_whitespace() {
+        let component = Component {
+            content: Some(ComponentNode {
+                node: Node::Button(ButtonNode {
+                    content: "a\r\nb".to_string(),
+                    message: "{\"a\"\n\t& <b>}".to_string(),
+                }),
+                layout: NodeLayout::default(),
+                spans: Spans::default(),
+            }),
+            message_type: None,
+            model_type: None,
+            templates: Vec::new(),
+            props: Vec::new(),
+            spans: Spans::default(),
+        };
+
+        assert_eq!(parse_stripped(&to_ixml(&component)), component);
+    }
+
+    #[test]
+    fn can_write_props_and_templates() {
+        let raw_window = r#"<Component>
+  <Props>
+    <Prop Name="greeting" Type="String" Default="Hello" />
+  </Props>
+  <Template Name="Label" Params="value">
+    <Text Size="20">{value}</Text>
+  </Template>
+  <Use Template="Label" value="Hello" />
+</Component>"#;
+
+        let component = parse_stripped(raw_window);
+        let written = to_ixml(&component);
+
+        assert_eq!(
+            written,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<Component>
+  <Props>
+    <Prop Name="greeting" Type="String" Default="Hello" />
+  </Props>
+  <Template Name="Label" Params="value">
+    <Text Size="20">{value}</Text>
+  </Template>
+  <Text Size="20">{"Hello"}</Text>
+</Component>
+"#
+        );
+        assert_eq!(parse_stripped(&written), component);
+    }
+
+    #[test]
+    fn parse_write_parse_roundtrips() {
+        let mut rng = StdRng::seed_from_u64(0x1ced);
+        for _ in 0..500 {
+            let component = Component {
+                content: rng.random_bool(0.9).then(|| random_node(&mut rng, 0)),
+                message_type: random_option(&mut rng),
+                model_type: random_option(&mut rng),
+                templates: Vec::new(),
+                props: Vec::new(),
+                spans: Spans::default(),
+            };
+
+            let written = to_ixml(&component);
+            let mut parsed =
+                parse_str(&written).unwrap_or_else(|e| panic!("invalid template: {e}\n{written}"));
+            parsed.strip_spans();
+            assert_eq!(parsed, component, "{written}");
+            assert_eq!(to_ixml(&parsed), written);
+        }
+    }
+
+    /// Parses a template written in a test, without the spans the expected trees don't have.
+    fn parse_stripped(source: &str) -> Component {
+        let mut component = parse_str(source).unwrap();
+        component.strip_spans();
+        component
+    }
+
+    fn text(content: &str) -> ComponentNode {
+        ComponentNode {
+            node: Node::Text(TextNode {
+                content: content.to_string(),
+                size: DEFAULT_TEXT_SIZE,
+                color: None,
+                font: None,
+            }),
+            layout: NodeLayout::default(),
+            spans: Spans::default(),
+        }
+    }
+
+    fn random_string(rng: &mut StdRng) -> String {
+        const CHARS: &[char] = &[
+            'a', 'Z', '0', ' ', '{', '}', '&', '<', '>', '"', '\'', ';', '#', '\n', '\r', '\t',
+            'é', '→', '🦀',
+        ];
+        let len = rng.random_range(0..8);
+        (0..len).map(|_| *CHARS.choose(rng).unwrap()).collect()
+    }
+
+    fn random_option(rng: &mut StdRng) -> Option<String> {
+        rng.random_bool(0.5).then(|| random_string(rng))
+    }
+
+    fn random_f32(rng: &mut StdRng) -> f32 {
+        match rng.random_range(0..3) {
+            0 => 0.0,
+            1 => rng.random_range(0..100) as f32,
+            _ => rng.random_range(0.0..1e6),
+        }
+    }
+
+    fn random_length(rng: &mut StdRng) -> Length {
+        match rng.random_range(0..4) {
+            0 => Length::Fill,
+            1 => Length::FillPortion(rng.random_range(1..10)),
+            2 => Length::Shrink,
+            _ => Length::Fixed(random_f32(rng)),
+        }
+    }
+
+    fn random_node(rng: &mut StdRng, depth: usize) -> ComponentNode {
+        let layout = NodeLayout {
+            align_x: *[
+                HorizontalAlignment::Left,
+                HorizontalAlignment::Center,
+                HorizontalAlignment::Right,
+                HorizontalAlignment::Stretch,
+            ]
+            .choose(rng)
+            .unwrap(),
+            align_y: *[
+                VerticalAlignment::Top,
+                VerticalAlignment::Center,
+                VerticalAlignment::Bottom,
+                VerticalAlignment::Stretch,
+            ]
+            .choose(rng)
+            .unwrap(),
+            padding: Padding {
+                value: random_f32(rng),
+            },
+            width: random_length(rng),
+            height: random_length(rng),
+        };
+        let leaf = depth >= 3;
+        let node = match rng.random_range(0..if leaf { 2 } else { 5 }) {
+            0 => Node::Button(ButtonNode {
+                content: random_string(rng),
+                message: random_string(rng),
+            }),
+            1 => Node::Text(TextNode {
+                content: random_string(rng),
+                size: random_f32(rng),
+                color: rng
+                    .random_bool(0.5)
+                    .then(|| Color::rgba8(rng.random(), rng.random(), rng.random(), rng.random())),
+                font: rng.random_bool(0.5).then(|| {
+                    [
+                        Font::Default,
+                        Font::Monospace,
+                        Font::Serif,
+                        Font::SansSerif,
+                        Font::Named("Fira Sans".to_string()),
+                    ]
+                    .choose(rng)
+                    .unwrap()
+                    .clone()
+                }),
+            }),
+            2 => Node::Column(ColumnNode {
+                content: random_children(rng, depth),
+            }),
+            3 => Node::Row(RowNode {
+                content: random_children(rng, depth),
+            }),
+            _ => Node::Match(MatchNode {
+                value: random_string(rng),
+                cases: (0..rng.random_range(0..3))
+                    .map(|_| MatchCaseNode {
+                        case: random_string(rng),
+                        destructure: random_option(rng),
+                        content: rng
+                            .random_bool(0.8)
+                            .then(|| Box::new(random_node(rng, depth + 1))),
+                        spans: Spans::default(),
+                    })
+                    .collect(),
+            }),
+        };
+
+        ComponentNode {
+            node,
+            layout,
+            spans: Spans::default(),
+        }
+    }
+
+    #[allow(clippy::vec_box)]
+    fn random_children(rng: &mut StdRng, depth: usize) -> Vec<Box<ComponentNode>> {
+        (0..rng.random_range(0..4))
+            .map(|_| Box::new(random_node(rng, depth + 1)))
+            .collect()
+    }
+}
@@ -4,7 +4,12 @@ use std::{
     str::FromStr,
 };
 
-use iced::{Element, Subscription, Task, widget::text};
+use iced::{
+    Element, Subscription, Task,
+    widget::{column, row, text, text_input},
+};
+use iced_xml_core::{Component, ComponentNode, Node, PropNode};
+use iced_xml_parse::ParseOptions;
 use preview::ComponentNodePreview;
 mod preview;
 
@@ -13,7 +18,7 @@ const DEFAULT_IXML: &str = include_str!("./hello_world.ixml");
 fn main() -> iced::Result {
     let args: Vec<String> = env::args().collect();
     let initial_path = match &args[..] {
-        [_, path] => PathBuf::from_str(&path).ok(),
+        [_, path] => PathBuf::from_str(path).ok(),
         _ => None,
     };
     iced::application("IXML-Previewer", App::update, App::view)
@@ -24,21 +29,29 @@ fn main() -> iced::Result {
 #[derive(Debug, Clone)]
 enum Message {
     Tick,
+    #[allow(dead_code)] // TODO: Send from a file picker
     PathChanged(PathBuf),
-    NOP,
+    PropChanged(String, String),
+    Nop,
 }
 
 #[derive(Debug, Default)]
 struct App {
-    parsed: Option<iced_xml_core::Component>,
+    parsed: Option<Result<iced_xml_parse::Parsed, iced_xml_parse::Error>>,
+    /// The parsed component showing the values of its props
+    shown: Option<Component>,
     path: Option<PathBuf>,
+    /// The values of the props of the template, edited in the previewer
+    props: Vec<(String, String)>,
 }
 
 impl App {
     pub fn with_initial_path<P: AsRef<Path>>(path: Option<P>) -> Self {
         Self {
             parsed: None,
+            shown: None,
             path: path.map(|p| p.as_ref().into()),
+            props: Vec::new(),
         }
     }
 
@@ -49,23 +62,191 @@ impl App {
     fn update(&mut self, message: Message) {
         match message {
             Message::Tick => {
+                // Render as much as possible while the template is being edited
+                let options = ParseOptions::lenient();
                 self.parsed = match &self.path {
-                    None => Some(iced_xml_parse::parse_str(DEFAULT_IXML)),
-                    Some(path) => Some(iced_xml_parse::parse_file(path)),
+                    None => Some(iced_xml_parse::parse_str_with(DEFAULT_IXML, &options)),
+                    Some(path) => Some(iced_xml_parse::parse_file_with(path, &options)),
+                };
+                if let Some(Ok(parsed)) = &self.parsed {
+                    // Edited values are kept while the template changes, new props start from
+                    // their default
+                    self.props = parsed
+                        .component
+                        .props
+                        .iter()
+                        .map(|prop| prop_value(prop, &self.props))
+                        .collect();
                 }
+                self.show_props();
             }
             Message::PathChanged(path) => self.path = Some(path),
-            Message::NOP => {}
+            Message::PropChanged(name, value) => {
+                if let Some(prop) = self.props.iter_mut().find(|(prop, _)| *prop == name) {
+                    prop.1 = value;
+                }
+                // The template is parsed again on the next tick
+                self.show_props();
+            }
+            Message::Nop => {}
         }
     }
 
+    /// Shows the edited values of the props in the parsed component.
+    fn show_props(&mut self) {
+        self.shown = match &self.parsed {
+            Some(Ok(parsed)) => {
+                let mut component = parsed.component.clone();
+                let templates = component
+                    .templates
+                    .iter_mut()
+                    .filter_map(|template| template.content.as_deref_mut());
+                for content in component.content.iter_mut().chain(templates) {
+                    show_props(content, &self.props);
+                }
+                Some(component)
+            }
+            _ => None,
+        };
+    }
+
     fn view(&self) -> Element<'_, Message> {
-        match &self.parsed {
-            None => text!("Invalid xml").into(),
-            Some(parsed) => match &parsed.content {
-                None => text!("").into(),
-                Some(content) => content.view().map(|_| Message::NOP),
-            },
+        match (&self.parsed, &self.shown) {
+            (None, _) | (Some(Ok(_)), None) => text!("").into(),
+            (Some(Err(e)), _) => describe(e).into(),
+            (Some(Ok(parsed)), Some(shown)) => {
+                let content = match &shown.content {
+                    None => text!("").into(),
+                    Some(content) => content.view().map(|_| Message::Nop),
+                };
+                // Templates are shown as written, their parameters standing in for arguments
+                let templates = shown.templates.iter().map(|template| {
+                    let params = template.params.join(", ");
+                    let content = match &template.content {
+                        None => text!("").into(),
+                        Some(content) => content.view().map(|_| Message::Nop),
+                    };
+                    column![
+                        text!("Template {}({params})", template.name).size(12),
+                        content
+                    ]
+                    .into()
+                });
+                let props =
+                    parsed
+                        .component
+                        .props
+                        .iter()
+                        .zip(&self.props)
+                        .map(|(prop, (name, value))| {
+                            let input = text_input("", value)
+                                .size(12)
+                                .on_input(|value| Message::PropChanged(name.clone(), value));
+                            row![text!("{name}: {}", prop.ty).size(12), input]
+                                .spacing(10)
+                                .into()
+                        });
+                let warnings = parsed.warnings.iter().map(|w| describe(w).into());
+                column(props)
+                    .push(content)
+                    .extend(templates)
+                    .extend(warnings)
+                    .into()
+            }
+        }
+    }
+}
+
+fn describe(error: &iced_xml_parse::Error) -> iced::widget::Text<'_> {
+    match error.position() {
+        Some(position) => text!("{position}: {error}"),
+        None => text!("{error}"),
+    }
+}
+
+fn prop_value(prop: &PropNode, edited: &[(String, String)]) -> (String, String) {
+    match edited.iter().find(|(name, _)| *name == prop.name) {
+        Some(edited) => edited.clone(),
+        None => {
+            let default = trim_braces(prop.default.as_deref().unwrap_or(""));
+            (prop.name.clone(), default.to_string())
+        }
+    }
+}
+
+/// Shows the edited values of the props wherever the template uses them. An expression which
+/// is just a prop, e.g. `{&self.label}`, shows its value, other expressions show the value in
+/// place of the prop, e.g. `{self.count + 1}`.
+fn show_props(node: &mut ComponentNode, props: &[(String, String)]) {
+    match &mut node.node {
+        Node::Button(button) => show_prop_values(&mut button.content, props),
+        Node::Text(text) => show_prop_values(&mut text.content, props),
+        Node::Column(column) => column.content.iter_mut().for_each(|c| show_props(c, props)),
+        Node::Row(row) => row.content.iter_mut().for_each(|c| show_props(c, props)),
+        Node::Match(match_node) => {
+            // Picks the case previewed when the value is a prop
+            show_prop_values(&mut match_node.value, props);
+            match_node
+                .cases
+                .iter_mut()
+                .filter_map(|case| case.content.as_mut())
+                .for_each(|c| show_props(c, props))
         }
+        Node::Placeholder(_) => {}
+    }
+}
+
+fn show_prop_values(content: &mut String, props: &[(String, String)]) {
+    if !(content.starts_with('{') && content.ends_with('}')) {
+        return;
+    }
+    if let Some(field) = iced_xml_parse::self_field(content)
+        && let Some((_, value)) = props.iter().find(|(name, _)| *name == field)
+    {
+        *content = value.clone();
+        return;
+    }
+
+    let values = props
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.clone()))
+        .collect();
+    *content = iced_xml_parse::substitute_self_fields(content, &values);
+}
+
+fn trim_braces(expr: &str) -> &str {
+    expr.strip_prefix('{')
+        .and_then(|expr| expr.strip_suffix('}'))
+        .unwrap_or(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shown(content: &str) -> String {
+        let props = [
+            ("label".to_string(), "Hello".to_string()),
+            ("count".to_string(), "5".to_string()),
+        ];
+        let mut content = content.to_string();
+        show_prop_values(&mut content, &props);
+        content
+    }
+
+    #[test]
+    fn shows_props_in_expressions() {
+        assert_eq!(shown("{&self.label}"), "Hello");
+        assert_eq!(shown("{self.count + self.counter}"), "{5 + self.counter}");
+        assert_eq!(
+            shown("{self.count(1) + other.self.count}"),
+            "{self.count(1) + other.self.count}"
+        );
+        assert_eq!(shown("self.label"), "self.label");
+        assert_eq!(shown("{(self.label)}"), "Hello");
+        assert_eq!(
+            shown("{format!(\"self.count {}\", self.count)}"),
+            "{format!(\"self.count {}\", 5)}"
+        );
     }
 }
@@ -1,5 +1,5 @@
 use iced::{
-    Element, alignment,
+    Length,
     widget::{Container, button, column, container, text},
 };
 use iced_xml_core::NodeLayout;
@@ -12,30 +12,31 @@ pub(crate) trait TypedNodePreview {
     fn view(&self, layout: iced_xml_core::NodeLayout) -> iced::Element<'_, DummyMessage>;
 }
 
+/// Aligns, pads and sizes a node like the generated code does: aligned nodes fill the space
+/// given by their parent to be aligned within it, unless they are given a size.
 fn layout_container(
     layout: NodeLayout,
     content: iced::Element<'_, DummyMessage>,
 ) -> Container<'_, DummyMessage> {
-    let align_x = match layout.align_x {
-        iced_xml_core::HorizontalAlignment::Left => alignment::Horizontal::Left,
-        iced_xml_core::HorizontalAlignment::Center => alignment::Horizontal::Center,
-        iced_xml_core::HorizontalAlignment::Right => alignment::Horizontal::Right,
-        iced_xml_core::HorizontalAlignment::Stretch => todo!(),
+    let aligned = |length: Length| match length {
+        Length::Shrink => Length::Fill,
+        length => length,
     };
-
-    let align_y = match layout.align_y {
-        iced_xml_core::VerticalAlignment::Top => alignment::Vertical::Top,
-        iced_xml_core::VerticalAlignment::Center => alignment::Vertical::Center,
-        iced_xml_core::VerticalAlignment::Bottom => alignment::Vertical::Bottom,
-        iced_xml_core::VerticalAlignment::Stretch => todo!(),
+    let (width, height) = (Length::from(layout.width), Length::from(layout.height));
+    let content = container(content).padding(iced::Padding::new(layout.padding.value));
+    let content = match layout.align_x {
+        iced_xml_core::HorizontalAlignment::Left => content.width(width),
+        iced_xml_core::HorizontalAlignment::Center => content.center_x(aligned(width)),
+        iced_xml_core::HorizontalAlignment::Right => content.align_right(aligned(width)),
+        iced_xml_core::HorizontalAlignment::Stretch => content.width(Length::Fill),
     };
 
-    let padding = iced::Padding::new(layout.padding.value);
-
-    container(content)
-        .align_x(align_x)
-        .align_y(align_y)
-        .padding(padding)
+    match layout.align_y {
+        iced_xml_core::VerticalAlignment::Top => content.height(height),
+        iced_xml_core::VerticalAlignment::Center => content.center_y(aligned(height)),
+        iced_xml_core::VerticalAlignment::Bottom => content.align_bottom(aligned(height)),
+        iced_xml_core::VerticalAlignment::Stretch => content.height(Length::Fill),
+    }
 }
 
 impl ComponentNodePreview for iced_xml_core::ComponentNode {
@@ -45,6 +46,8 @@ impl ComponentNodePreview for iced_xml_core::ComponentNode {
             iced_xml_core::Node::Text(text_node) => text_node.view(self.layout),
             iced_xml_core::Node::Column(column_node) => column_node.view(self.layout),
             iced_xml_core::Node::Row(row_node) => row_node.view(self.layout),
+            iced_xml_core::Node::Match(match_node) => match_node.view(self.layout),
+            iced_xml_core::Node::Placeholder(placeholder) => placeholder.view(self.layout),
         }
     }
 }
@@ -58,7 +61,13 @@ impl TypedNodePreview for iced_xml_core::ButtonNode {
 
 impl TypedNodePreview for iced_xml_core::TextNode {
     fn view(&self, layout: iced_xml_core::NodeLayout) -> iced::Element<'_, DummyMessage> {
-        let text = text!("{}", self.content).size(self.size);
+        let text = text!("{}", self.content)
+            .size(self.size)
+            .color_maybe(self.color.map(iced::Color::from));
+        let text = match &self.font {
+            Some(font) => text.font(font),
+            None => text,
+        };
         layout_container(layout, text.into()).into()
     }
 }
@@ -78,3 +87,35 @@ impl TypedNodePreview for iced_xml_core::RowNode {
         layout_container(layout, column.into()).into()
     }
 }
+
+impl TypedNodePreview for iced_xml_core::MatchNode {
+    fn view(&self, layout: iced_xml_core::NodeLayout) -> iced::Element<'_, DummyMessage> {
+        // Without a model to match against, preview the case of a variant given by a prop, or
+        // the first case
+        let variant = |pattern: &str| {
+            let path = pattern.split(['(', '{']).next().unwrap_or_default();
+            path.rsplit("::")
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        };
+        let value = variant(&self.value);
+        let content = self
+            .cases
+            .iter()
+            .filter(|case| !value.is_empty() && variant(&case.case) == value)
+            .chain(&self.cases)
+            .find_map(|case| case.content.as_ref())
+            .map(|c| c.view())
+            .unwrap_or_else(|| column![].into());
+        layout_container(layout, content).into()
+    }
+}
+
+impl TypedNodePreview for iced_xml_core::PlaceholderNode {
+    fn view(&self, layout: iced_xml_core::NodeLayout) -> iced::Element<'_, DummyMessage> {
+        let placeholder = container(text!("<{}>", self.name)).style(container::bordered_box);
+        layout_container(layout, placeholder.into()).into()
+    }
+}
@@ -0,0 +1,507 @@
+//! A small interpreter for the subset of rust expressions used inside templates:
+//! literals, `self.field`, `self.method(..)`, `function(..)`, `Variant(..)` and the
+//! names bound by `MatchCase`s.
+
+use crate::{Error, Reflect, Value};
+
+/// Values bound by the patterns of the enclosing `MatchCase`s, innermost last. `None` for
+/// values which don't convert into a [`Value`].
+pub(crate) type Bindings = Vec<(String, Option<Value>)>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Expr {
+    pub(crate) kind: ExprKind,
+    /// The expression as written in the template, for error messages
+    pub(crate) source: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ExprKind {
+    Lit(Value),
+    SelfRef,
+    Path(String),
+    Field(Box<Expr>, String),
+    Call {
+        receiver: Option<Box<Expr>>,
+        name: String,
+        args: Vec<Expr>,
+    },
+}
+
+fn is_iced_expr(expr: &str) -> bool {
+    expr.starts_with('{') && expr.ends_with('}')
+}
+
+/// Parses an attribute or text value, which is either a `{expr}` or a literal string.
+pub(crate) fn parse_expr_or_lit(raw: &str) -> Result<Expr, Error> {
+    let trimmed = raw.trim();
+    if is_iced_expr(trimmed) {
+        parse_expr(&trimmed[1..trimmed.len() - 1])
+    } else {
+        Ok(Expr {
+            kind: ExprKind::Lit(Value::String(raw.to_string())),
+            source: raw.to_string(),
+        })
+    }
+}
+
+/// Parses a `Message` attribute, which is an expression with or without braces, like in
+/// generated code.
+pub(crate) fn parse_message(raw: &str) -> Result<Expr, Error> {
+    let trimmed = raw.trim();
+    if is_iced_expr(trimmed) {
+        parse_expr(&trimmed[1..trimmed.len() - 1])
+    } else {
+        parse_expr(trimmed)
+    }
+}
+
+pub(crate) fn parse_expr(raw: &str) -> Result<Expr, Error> {
+    let mut parser = Parser {
+        raw,
+        chars: raw.char_indices().peekable(),
+    };
+    let expr = parser.expr()?;
+    parser.skip_whitespace();
+    match parser.chars.peek().map(|(_, c)| *c) {
+        None => Ok(expr),
+        Some(c) => Err(parser.error(format!("unexpected '{c}'"))),
+    }
+}
+
+struct Parser<'a> {
+    raw: &'a str,
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl Parser<'_> {
+    fn error(&self, reason: impl Into<String>) -> Error {
+        Error::InvalidExpr {
+            expr: self.raw.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    fn position(&mut self) -> usize {
+        self.chars.peek().map_or(self.raw.len(), |(i, _)| *i)
+    }
+
+    /// The expression starting at `start` and ending before the current character.
+    fn expr_since(&mut self, start: usize, kind: ExprKind) -> Expr {
+        let end = self.position();
+        Expr {
+            kind,
+            source: self.raw[start..end].trim_end().to_string(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        self.chars.next_if(|(_, c)| *c == expected).is_some()
+    }
+
+    fn expr(&mut self) -> Result<Expr, Error> {
+        self.skip_whitespace();
+        let start = self.position();
+        // References make no difference to the interpreter
+        while self.eat('&') {}
+
+        let kind = self.primary()?;
+        let mut expr = self.expr_since(start, kind);
+        while self.eat('.') {
+            let name = self.ident()?;
+            let kind = if self.eat('(') {
+                ExprKind::Call {
+                    receiver: Some(Box::new(expr)),
+                    name,
+                    args: self.args()?,
+                }
+            } else {
+                ExprKind::Field(Box::new(expr), name)
+            };
+            expr = self.expr_since(start, kind);
+        }
+
+        Ok(expr)
+    }
+
+    fn primary(&mut self) -> Result<ExprKind, Error> {
+        self.skip_whitespace();
+        match self.chars.peek().map(|(_, c)| *c) {
+            Some('"') => self.string().map(|s| ExprKind::Lit(Value::String(s))),
+            Some('(') => {
+                self.chars.next();
+                let expr = self.expr()?;
+                if self.eat(')') {
+                    Ok(expr.kind)
+                } else {
+                    Err(self.error("expected ')'"))
+                }
+            }
+            Some(c) if c.is_ascii_digit() || c == '-' => self.number(),
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let mut path = self.ident()?;
+                while self.eat(':') {
+                    if !self.eat(':') {
+                        return Err(self.error("expected '::'"));
+                    }
+                    path = self.ident()?;
+                }
+
+                match path.as_str() {
+                    "self" => Ok(ExprKind::SelfRef),
+                    "true" => Ok(ExprKind::Lit(Value::Bool(true))),
+                    "false" => Ok(ExprKind::Lit(Value::Bool(false))),
+                    _ if self.eat('(') => Ok(ExprKind::Call {
+                        receiver: None,
+                        name: path,
+                        args: self.args()?,
+                    }),
+                    _ => Ok(ExprKind::Path(path)),
+                }
+            }
+            Some(c) => Err(self.error(format!("unexpected '{c}'"))),
+            None => Err(self.error("unexpected end of expression")),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, Error> {
+        self.skip_whitespace();
+        let mut ident = String::new();
+        while let Some((_, c)) = self
+            .chars
+            .next_if(|(_, c)| c.is_alphanumeric() || *c == '_')
+        {
+            ident.push(c);
+        }
+
+        if ident.is_empty() {
+            Err(self.error("expected identifier"))
+        } else {
+            Ok(ident)
+        }
+    }
+
+    fn args(&mut self) -> Result<Vec<Expr>, Error> {
+        let mut args = Vec::new();
+        if self.eat(')') {
+            return Ok(args);
+        }
+
+        loop {
+            args.push(self.expr()?);
+            if self.eat(')') {
+                return Ok(args);
+            }
+            if !self.eat(',') {
+                return Err(self.error("expected ',' or ')'"));
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.chars.next();
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(value),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, c)) => value.push(c),
+                    None => break,
+                },
+                Some((_, c)) => value.push(c),
+                None => break,
+            }
+        }
+
+        Err(self.error("unterminated string literal"))
+    }
+
+    fn number(&mut self) -> Result<ExprKind, Error> {
+        let mut raw = String::new();
+        while let Some((_, c)) = self
+            .chars
+            .next_if(|(_, c)| c.is_ascii_digit() || matches!(c, '-' | '.' | '_'))
+        {
+            if c != '_' {
+                raw.push(c);
+            }
+        }
+
+        if let Ok(int) = raw.parse::<i64>() {
+            Ok(ExprKind::Lit(Value::Int(int)))
+        } else if let Ok(float) = raw.parse::<f64>() {
+            Ok(ExprKind::Lit(Value::Float(float)))
+        } else {
+            Err(self.error(format!("invalid number '{raw}'")))
+        }
+    }
+}
+
+/// Evaluates an expression to a value, looking up fields and methods on the model.
+pub(crate) fn eval<M: Reflect + ?Sized>(
+    expr: &Expr,
+    model: &M,
+    bindings: &Bindings,
+) -> Result<Value, Error> {
+    match &expr.kind {
+        ExprKind::Lit(value) => Ok(value.clone()),
+        // Only matched, so only enums evaluate
+        ExprKind::SelfRef => {
+            model
+                .variant()
+                .map(Value::Variant)
+                .ok_or_else(|| Error::InvalidExpr {
+                    expr: expr.source.clone(),
+                    reason: "only models which are enums can be matched".to_string(),
+                })
+        }
+        ExprKind::Path(name) => match bindings.iter().rev().find(|(binding, _)| binding == name) {
+            Some((_, Some(value))) => Ok(value.clone()),
+            Some((_, None)) => Err(Error::Unreflected(expr.source.clone())),
+            None => Err(Error::InvalidExpr {
+                expr: expr.source.clone(),
+                reason: "only names bound by a MatchCase can be evaluated".to_string(),
+            }),
+        },
+        ExprKind::Field(receiver, name) if receiver.kind == ExprKind::SelfRef => model
+            .field(name)
+            .ok_or_else(|| Error::UnknownField(name.clone()))?,
+        ExprKind::Field(receiver, name) => match eval(receiver, model, bindings)? {
+            Value::Variant(variant) => match variant.field(name) {
+                Some(Some(value)) => Ok(value.clone()),
+                Some(None) => Err(Error::Unreflected(expr.source.clone())),
+                None => Err(Error::UnknownField(name.clone())),
+            },
+            _ => Err(Error::InvalidExpr {
+                expr: expr.source.clone(),
+                reason: "only fields of self and of variants can be evaluated".to_string(),
+            }),
+        },
+        ExprKind::Call {
+            receiver: None,
+            name,
+            args,
+        } => call(model, name, args, bindings),
+        ExprKind::Call {
+            receiver: Some(receiver),
+            name,
+            args,
+        } if receiver.kind == ExprKind::SelfRef => call(model, name, args, bindings),
+        ExprKind::Call { .. } => Err(Error::InvalidExpr {
+            expr: expr.source.clone(),
+            reason: "only methods of self can be called".to_string(),
+        }),
+    }
+}
+
+fn call<M: Reflect + ?Sized>(
+    model: &M,
+    name: &str,
+    args: &[Expr],
+    bindings: &Bindings,
+) -> Result<Value, Error> {
+    let args = eval_args(args, model, bindings)?;
+    model
+        .call(name, &args)
+        .ok_or_else(|| Error::UnknownMethod(name.to_string()))?
+}
+
+fn eval_args<M: Reflect + ?Sized>(
+    args: &[Expr],
+    model: &M,
+    bindings: &Bindings,
+) -> Result<Vec<Value>, Error> {
+    args.iter().map(|arg| eval(arg, model, bindings)).collect()
+}
+
+/// Evaluates an expression to a message, e.g. `Increment` or `Completed(self.completed)`.
+pub(crate) fn eval_message<M: Reflect + ?Sized>(
+    expr: &Expr,
+    model: &M,
+    bindings: &Bindings,
+) -> Result<M::Message, Error> {
+    let (name, args) = match &expr.kind {
+        ExprKind::Path(name) => (name, Vec::new()),
+        ExprKind::Call {
+            receiver: None,
+            name,
+            args,
+        } => (name, eval_args(args, model, bindings)?),
+        _ => {
+            return Err(Error::InvalidExpr {
+                expr: expr.source.clone(),
+                reason: "expected a message variant".to_string(),
+            });
+        }
+    };
+
+    // `Message::Add(..)` names the same variant as `Add(..)`
+    let variant = name.rsplit("::").next().unwrap_or(name);
+    model
+        .message(variant, &args)
+        .ok_or_else(|| Error::UnknownMessage(name.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expr(source: &str, kind: ExprKind) -> Expr {
+        Expr {
+            kind,
+            source: source.to_string(),
+        }
+    }
+
+    #[test]
+    fn can_parse_field_access() {
+        let parsed = parse_expr_or_lit("{&self.value}").unwrap();
+
+        assert_eq!(
+            parsed,
+            expr(
+                "&self.value",
+                ExprKind::Field(
+                    Box::new(expr("&self", ExprKind::SelfRef)),
+                    "value".to_string()
+                )
+            )
+        );
+    }
+
+    #[test]
+    fn can_parse_message_with_args() {
+        let parsed = parse_expr("Message::Completed(self.done, 1)").unwrap();
+
+        assert_eq!(
+            parsed,
+            expr(
+                "Message::Completed(self.done, 1)",
+                ExprKind::Call {
+                    receiver: None,
+                    name: "Completed".to_string(),
+                    args: vec![
+                        expr(
+                            "self.done",
+                            ExprKind::Field(
+                                Box::new(expr("self", ExprKind::SelfRef)),
+                                "done".to_string()
+                            )
+                        ),
+                        expr("1", ExprKind::Lit(Value::Int(1)))
+                    ]
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn literal_text_is_not_parsed() {
+        let parsed = parse_expr_or_lit("Click me").unwrap();
+
+        assert_eq!(
+            parsed.kind,
+            ExprKind::Lit(Value::String("Click me".to_string()))
+        );
+    }
+
+    #[test]
+    fn bare_message_is_a_path() {
+        assert_eq!(
+            parse_message("Increment").unwrap().kind,
+            ExprKind::Path("Increment".to_string())
+        );
+        assert_eq!(
+            parse_message("{ Increment }").unwrap(),
+            expr("Increment", ExprKind::Path("Increment".to_string()))
+        );
+    }
+
+    #[test]
+    fn eval_error_shows_template_text() {
+        struct Model;
+
+        impl Reflect for Model {
+            type Message = ();
+
+            fn field(&self, _name: &str) -> Option<Result<Value, Error>> {
+                None
+            }
+
+            fn message(&self, _name: &str, _args: &[Value]) -> Option<Self::Message> {
+                None
+            }
+        }
+
+        assert_eq!(
+            eval(&parse_expr("Filter::All").unwrap(), &Model, &Vec::new()),
+            Err(Error::InvalidExpr {
+                expr: "Filter::All".to_string(),
+                reason: "only names bound by a MatchCase can be evaluated".to_string(),
+            })
+        );
+        assert_eq!(
+            eval_message(
+                &parse_message("{self.message()}").unwrap(),
+                &Model,
+                &Vec::new()
+            ),
+            Err(Error::InvalidExpr {
+                expr: "self.message()".to_string(),
+                reason: "expected a message variant".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn can_eval_bindings_and_their_fields() {
+        struct Model;
+
+        impl Reflect for Model {
+            type Message = ();
+
+            fn field(&self, _name: &str) -> Option<Result<Value, Error>> {
+                None
+            }
+
+            fn message(&self, _name: &str, _args: &[Value]) -> Option<Self::Message> {
+                None
+            }
+        }
+
+        let bindings = vec![
+            (
+                "task".to_string(),
+                Some(Value::Variant(crate::Variant {
+                    name: "Task".to_string(),
+                    fields: vec![("done".to_string(), Some(Value::Bool(true)))],
+                })),
+            ),
+            ("state".to_string(), None),
+        ];
+        let eval = |expr| eval(&parse_expr(expr).unwrap(), &Model, &bindings);
+
+        assert_eq!(eval("(task.done)"), Ok(Value::Bool(true)));
+        assert_eq!(
+            eval("task.due"),
+            Err(Error::UnknownField("due".to_string()))
+        );
+        assert_eq!(
+            eval("&state"),
+            Err(Error::Unreflected("&state".to_string()))
+        );
+    }
+
+    #[test]
+    fn invalid_expr_complains() {
+        assert!(parse_expr("self.").is_err());
+        assert!(parse_expr("self.value +").is_err());
+    }
+}
@@ -0,0 +1,315 @@
+//! Re-renders templates from disk while an app is running.
+//!
+//! The first time a template is viewed, its modification time is recorded. Once the
+//! file changes, it is parsed again and rendered through the interpreter, for as long
+//! as it parses and all of its bindings resolve against the model. Otherwise [`view`]
+//! returns `None` and the caller falls back to its compiled view.
+//!
+//! Components derived with the `hot-reload` feature of `iced-xml-derive` render through
+//! here once [`install`] was called.
+//!
+//! Iced only rebuilds the view after an update, so apps need to produce messages
+//! (e.g. from `iced::time::every`) for changes to show up while idle.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, LazyLock, Mutex},
+    time::SystemTime,
+};
+
+use iced::Element;
+use iced_xml_core::{
+    Component,
+    reload::{AnyMessage, Reload},
+};
+pub use iced_xml_parse::ParseOptions;
+use iced_xml_parse::Parsed;
+
+use crate::Reflect;
+
+struct Template {
+    /// The template and the files it includes or imports, with their modification times
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+    /// `None` until a file changed, or if the latest change failed to parse
+    component: Option<Arc<Component>>,
+}
+
+impl Template {
+    fn changed(&self) -> bool {
+        self.files
+            .iter()
+            .any(|(path, modified)| self::modified(path) != *modified)
+    }
+}
+
+static TEMPLATES: LazyLock<Mutex<HashMap<PathBuf, Template>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Renders the template at `path` against `model`, if it changed since it was first viewed.
+pub fn view<'a, M>(path: impl AsRef<Path>, model: &M) -> Option<Element<'a, M::Message>>
+where
+    M: Reflect + ?Sized,
+    M::Message: Clone + 'a,
+{
+    view_with(path, &ParseOptions::default(), model)
+}
+
+/// Like [`view`], parsing the template with `options`.
+pub fn view_with<'a, M>(
+    path: impl AsRef<Path>,
+    options: &ParseOptions,
+    model: &M,
+) -> Option<Element<'a, M::Message>>
+where
+    M: Reflect + ?Sized,
+    M::Message: Clone + 'a,
+{
+    let path = path.as_ref();
+    let component = {
+        let mut templates = TEMPLATES.lock().ok()?;
+        let template = templates.entry(path.to_path_buf()).or_insert_with(|| {
+            // Parsed once to learn about includes, the compiled view is still up to date
+            let files = parse(path, options)
+                .map(|(files, _)| files)
+                .unwrap_or_else(|_| vec![path.to_path_buf()]);
+            Template {
+                files: watch(files),
+                component: None,
+            }
+        });
+        if template.changed() {
+            let (files, component) = reload(path, options, &template.files);
+            template.files = files;
+            template.component = component.map(Arc::new);
+        }
+        template.component.clone()?
+    };
+
+    match crate::view(&component, model) {
+        Ok(view) => Some(view),
+        Err(e) => {
+            // Only report once, the template is retried after its next change
+            eprintln!("{}: {e}, falling back to compiled view", path.display());
+            if let Ok(mut templates) = TEMPLATES.lock()
+                && let Some(template) = templates.get_mut(path)
+                && template
+                    .component
+                    .as_ref()
+                    .is_some_and(|c| Arc::ptr_eq(c, &component))
+            {
+                template.component = None;
+            }
+            None
+        }
+    }
+}
+
+/// Lets views derived with the `hot-reload` feature render their templates through
+/// [`view_with`], otherwise they keep using their compiled view.
+pub fn install() {
+    iced_xml_core::reload::install(Interpreter);
+}
+
+struct Interpreter;
+
+impl Reload for Interpreter {
+    fn view<'a>(
+        &self,
+        path: &str,
+        lenient: bool,
+        model: &dyn Reflect<Message = AnyMessage>,
+    ) -> Option<Element<'a, AnyMessage>> {
+        let options = if lenient {
+            ParseOptions::lenient()
+        } else {
+            ParseOptions::strict()
+        };
+        view_with(path, &options, model)
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn watch(paths: impl IntoIterator<Item = PathBuf>) -> Vec<(PathBuf, Option<SystemTime>)> {
+    paths
+        .into_iter()
+        .map(|path| {
+            let modified = modified(&path);
+            (path, modified)
+        })
+        .collect()
+}
+
+/// Parses the template at `path`, along with the files it includes or imports.
+fn parse(
+    path: &Path,
+    options: &ParseOptions,
+) -> Result<(Vec<PathBuf>, Parsed), iced_xml_parse::Error> {
+    let mut parsed = iced_xml_parse::parse_file_with(path, options)?;
+    let files = std::iter::once(path.to_path_buf())
+        .chain(parsed.files.drain(..).map(|(_, path)| path))
+        .collect();
+    Ok((files, parsed))
+}
+
+/// Parses the template at `path` after a change, reporting what went wrong. If it fails to
+/// parse, the same files are watched for the next change.
+fn reload(
+    path: &Path,
+    options: &ParseOptions,
+    watched: &[(PathBuf, Option<SystemTime>)],
+) -> (Vec<(PathBuf, Option<SystemTime>)>, Option<Component>) {
+    let describe = |e: &iced_xml_parse::Error| {
+        let position = e.position().map(|p| format!(":{p}")).unwrap_or_default();
+        format!("{}{position}: {e}", path.display())
+    };
+    match parse(path, options) {
+        Ok((files, parsed)) => {
+            for warning in &parsed.warnings {
+                eprintln!("warning: {}", describe(warning));
+            }
+            (watch(files), Some(parsed.component))
+        }
+        Err(e) => {
+            eprintln!("{}, falling back to compiled view", describe(&e));
+            (watch(watched.iter().map(|(path, _)| path.clone())), None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::Value;
+
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    struct Message;
+
+    struct Model;
+
+    impl Reflect for Model {
+        type Message = Message;
+
+        fn field(&self, name: &str) -> Option<Result<Value, crate::Error>> {
+            (name == "value").then_some(Ok(Value::Int(1)))
+        }
+
+        fn message(&self, _name: &str, _args: &[Value]) -> Option<Self::Message> {
+            None
+        }
+    }
+
+    fn write_template(path: &Path, content: &str, modified: SystemTime) {
+        std::fs::write(
+            path,
+            format!(r#"<Component Message="Message">{content}</Component>"#),
+        )
+        .unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[test]
+    fn reloads_changed_template() {
+        let path = std::env::temp_dir().join(format!("hot_reload_{}.ixml", std::process::id()));
+        let start = SystemTime::now();
+
+        write_template(&path, r#"<Text Content="{self.value}" />"#, start);
+        assert!(view(&path, &Model).is_none());
+
+        write_template(
+            &path,
+            r#"<Text Content="{self.valeu}" />"#,
+            start + Duration::from_secs(1),
+        );
+        assert!(view(&path, &Model).is_none());
+
+        write_template(
+            &path,
+            r#"<Text Size="20" Content="{self.value}" />"#,
+            start + Duration::from_secs(2),
+        );
+        assert!(view(&path, &Model).is_some());
+
+        write_template(&path, r#"<Text"#, start + Duration::from_secs(3));
+        assert!(view(&path, &Model).is_none());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reloads_changed_include() {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("hot_reload_main_{}.ixml", std::process::id()));
+        let include = format!("hot_reload_include_{}.ixml", std::process::id());
+        let start = SystemTime::now();
+
+        write_template(&path, &format!(r#"<Include Source="{include}" />"#), start);
+        write_template(
+            &dir.join(&include),
+            r#"<Text Content="{self.value}" />"#,
+            start,
+        );
+        assert!(view(&path, &Model).is_none());
+
+        write_template(
+            &dir.join(&include),
+            r#"<Text Size="20" Content="{self.value}" />"#,
+            start + Duration::from_secs(1),
+        );
+        assert!(view(&path, &Model).is_some());
+
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(dir.join(include)).unwrap();
+    }
+
+    #[test]
+    fn reloads_leniently() {
+        let path =
+            std::env::temp_dir().join(format!("hot_reload_lenient_{}.ixml", std::process::id()));
+        let start = SystemTime::now();
+
+        write_template(&path, r#"<Text Content="{self.value}" />"#, start);
+        assert!(view_with(&path, &ParseOptions::lenient(), &Model).is_none());
+
+        write_template(
+            &path,
+            r#"<Text Colour="red" Content="{self.value}" />"#,
+            start + Duration::from_secs(1),
+        );
+        assert!(view_with(&path, &ParseOptions::lenient(), &Model).is_some());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn installed_reload_renders_derived_views() {
+        let path =
+            std::env::temp_dir().join(format!("hot_reload_install_{}.ixml", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        let start = SystemTime::now();
+
+        write_template(&path, r#"<Text Content="{self.value}" />"#, start);
+        install();
+        assert!(iced_xml_core::reload::view(path_str, false, &Model).is_none());
+
+        write_template(
+            &path,
+            r#"<Text Size="20" Content="{self.value}" />"#,
+            start + Duration::from_secs(1),
+        );
+        assert!(iced_xml_core::reload::view(path_str, false, &Model).is_some());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
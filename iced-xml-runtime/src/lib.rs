@@ -0,0 +1,6 @@
+mod expr;
+pub mod hot_reload;
+mod pattern;
+mod view;
+pub use iced_xml_core::reflect::{Error, Reflect, Value, Variant};
+pub use view::{check, view};
@@ -0,0 +1,396 @@
+//! Patterns of `MatchCase`s, matched against reflected values: variants with or without
+//! fields, bindings, `_`, `..` and literals.
+
+use crate::{Error, Value, expr::Bindings};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Pattern {
+    Wild,
+    Binding(String),
+    Lit(Value),
+    Variant {
+        name: String,
+        fields: Option<Fields>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Fields {
+    /// `None` marks the position of a `..`
+    Tuple(Vec<Option<Pattern>>),
+    /// Whether the fields end with a `..`
+    Struct(Vec<(String, Pattern)>, bool),
+}
+
+pub(crate) fn parse_pattern(raw: &str) -> Result<Pattern, Error> {
+    let mut parser = Parser {
+        raw,
+        chars: raw.char_indices().peekable(),
+    };
+    let pattern = parser.pattern()?;
+    parser.skip_whitespace();
+    match parser.chars.peek().map(|(_, c)| *c) {
+        None => Ok(pattern),
+        Some(c) => Err(parser.error(format!("unexpected '{c}'"))),
+    }
+}
+
+struct Parser<'a> {
+    raw: &'a str,
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl Parser<'_> {
+    fn error(&self, reason: impl Into<String>) -> Error {
+        Error::InvalidPattern {
+            pattern: self.raw.to_string(),
+            reason: reason.into(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        self.chars.next_if(|(_, c)| *c == expected).is_some()
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.peek().map(|(_, c)| *c)
+    }
+
+    /// Parses `..` if it comes next.
+    fn rest(&mut self) -> Result<bool, Error> {
+        if !self.eat('.') {
+            return Ok(false);
+        }
+        if !self.eat('.') {
+            return Err(self.error("expected '..'"));
+        }
+        Ok(true)
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, Error> {
+        // References make no difference to the interpreter
+        while self.eat('&') {}
+
+        match self.peek() {
+            Some('"') => self.string().map(|s| Pattern::Lit(Value::String(s))),
+            Some(c) if c.is_ascii_digit() || c == '-' => self.number(),
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let mut ident = self.ident()?;
+                while matches!(ident.as_str(), "ref" | "mut")
+                    && self.peek().is_some_and(|c| c.is_alphabetic() || c == '_')
+                {
+                    ident = self.ident()?;
+                }
+                let mut name = ident;
+                let mut is_path = false;
+                while self.eat(':') {
+                    if !self.eat(':') {
+                        return Err(self.error("expected '::'"));
+                    }
+                    name = self.ident()?;
+                    is_path = true;
+                }
+
+                if self.eat('(') {
+                    return Ok(Pattern::Variant {
+                        name,
+                        fields: Some(self.tuple_fields()?),
+                    });
+                }
+                if self.eat('{') {
+                    return Ok(Pattern::Variant {
+                        name,
+                        fields: Some(self.struct_fields()?),
+                    });
+                }
+                match name.as_str() {
+                    "_" => Ok(Pattern::Wild),
+                    "true" => Ok(Pattern::Lit(Value::Bool(true))),
+                    "false" => Ok(Pattern::Lit(Value::Bool(false))),
+                    // Like in rust, `Idle` names a variant and `idle` binds a value
+                    _ if is_path || name.starts_with(char::is_uppercase) => {
+                        Ok(Pattern::Variant { name, fields: None })
+                    }
+                    _ => Ok(Pattern::Binding(name)),
+                }
+            }
+            Some(c) => Err(self.error(format!("unexpected '{c}'"))),
+            None => Err(self.error("unexpected end of pattern")),
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, Error> {
+        self.skip_whitespace();
+        let mut ident = String::new();
+        while let Some((_, c)) = self
+            .chars
+            .next_if(|(_, c)| c.is_alphanumeric() || *c == '_')
+        {
+            ident.push(c);
+        }
+
+        if ident.is_empty() {
+            Err(self.error("expected identifier"))
+        } else {
+            Ok(ident)
+        }
+    }
+
+    fn tuple_fields(&mut self) -> Result<Fields, Error> {
+        let mut fields = Vec::new();
+        loop {
+            if self.eat(')') {
+                return Ok(Fields::Tuple(fields));
+            }
+            if self.rest()? {
+                fields.push(None);
+            } else {
+                fields.push(Some(self.pattern()?));
+            }
+            if !self.eat(',') && self.peek() != Some(')') {
+                return Err(self.error("expected ',' or ')'"));
+            }
+        }
+    }
+
+    fn struct_fields(&mut self) -> Result<Fields, Error> {
+        let mut fields = Vec::new();
+        loop {
+            if self.eat('}') {
+                return Ok(Fields::Struct(fields, false));
+            }
+            if self.rest()? {
+                return if self.eat('}') {
+                    Ok(Fields::Struct(fields, true))
+                } else {
+                    Err(self.error("expected '}' after '..'"))
+                };
+            }
+
+            while self.eat('&') {}
+            let mut name = self.ident()?;
+            while matches!(name.as_str(), "ref" | "mut") && self.peek() != Some(':') {
+                name = self.ident()?;
+            }
+            let pattern = if self.eat(':') {
+                self.pattern()?
+            } else {
+                Pattern::Binding(name.clone())
+            };
+            fields.push((name, pattern));
+            if !self.eat(',') && self.peek() != Some('}') {
+                return Err(self.error("expected ',' or '}'"));
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        self.chars.next();
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(value),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, c)) => value.push(c),
+                    None => break,
+                },
+                Some((_, c)) => value.push(c),
+                None => break,
+            }
+        }
+
+        Err(self.error("unterminated string literal"))
+    }
+
+    fn number(&mut self) -> Result<Pattern, Error> {
+        let mut raw = String::new();
+        while let Some((_, c)) = self
+            .chars
+            .next_if(|(_, c)| c.is_ascii_digit() || matches!(c, '-' | '_'))
+        {
+            if c != '_' {
+                raw.push(c);
+            }
+        }
+
+        raw.parse::<i64>()
+            .map(|int| Pattern::Lit(Value::Int(int)))
+            .map_err(|_| self.error(format!("invalid number '{raw}'")))
+    }
+}
+
+impl Pattern {
+    /// Matches `value`, adding the values it binds to `bindings`. `value` is `None` if it
+    /// does not convert into a [`Value`], which only matches bindings and `_`.
+    pub(crate) fn matches(
+        &self,
+        value: Option<&Value>,
+        bindings: &mut Bindings,
+        raw: &str,
+    ) -> Result<bool, Error> {
+        let unreflected = || Error::Unreflected(raw.to_string());
+        match self {
+            Pattern::Wild => Ok(true),
+            Pattern::Binding(name) => {
+                bindings.push((name.clone(), value.cloned()));
+                Ok(true)
+            }
+            Pattern::Lit(lit) => Ok(value.ok_or_else(unreflected)? == lit),
+            Pattern::Variant { name, fields } => {
+                let variant = match value.ok_or_else(unreflected)? {
+                    Value::Variant(variant) => variant,
+                    value => {
+                        return Err(Error::InvalidPattern {
+                            pattern: raw.to_string(),
+                            reason: format!("'{value}' is not an enum"),
+                        });
+                    }
+                };
+                if variant.name != *name {
+                    return Ok(false);
+                }
+
+                match fields {
+                    None => Ok(true),
+                    Some(Fields::Tuple(patterns)) => {
+                        let values = variant.fields.iter().map(|(_, value)| value.as_ref());
+                        let rest = patterns.iter().position(Option::is_none);
+                        let (before, after) = match rest {
+                            Some(rest) => (&patterns[..rest], &patterns[rest + 1..]),
+                            None => (&patterns[..], &[][..]),
+                        };
+                        let count = variant.fields.len();
+                        if before.len() + after.len() > count
+                            || rest.is_none() && before.len() != count
+                        {
+                            return Err(Error::InvalidPattern {
+                                pattern: raw.to_string(),
+                                reason: format!("'{name}' has {count} fields"),
+                            });
+                        }
+                        let values = values.collect::<Vec<_>>();
+                        let matched = before
+                            .iter()
+                            .zip(&values[..before.len()])
+                            .chain(after.iter().zip(&values[count - after.len()..]));
+                        for (pattern, value) in matched {
+                            let pattern = pattern.as_ref().expect("`..` only occurs once");
+                            if !pattern.matches(*value, bindings, raw)? {
+                                return Ok(false);
+                            }
+                        }
+                        Ok(true)
+                    }
+                    Some(Fields::Struct(patterns, _)) => {
+                        for (field, pattern) in patterns {
+                            let Some(value) = variant.field(field) else {
+                                return Err(Error::InvalidPattern {
+                                    pattern: raw.to_string(),
+                                    reason: format!("'{name}' has no field '{field}'"),
+                                });
+                            };
+                            if !pattern.matches(value.as_ref(), bindings, raw)? {
+                                return Ok(false);
+                            }
+                        }
+                        Ok(true)
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Variant;
+
+    use super::*;
+
+    fn loaded() -> Value {
+        Value::Variant(Variant {
+            name: "Loaded".to_string(),
+            fields: vec![
+                ("0".to_string(), Some(Value::Int(1))),
+                ("1".to_string(), None),
+            ],
+        })
+    }
+
+    fn matches(pattern: &str, value: &Value) -> Result<Option<Bindings>, Error> {
+        let mut bindings = Vec::new();
+        let matched = parse_pattern(pattern)?.matches(Some(value), &mut bindings, pattern)?;
+        Ok(matched.then_some(bindings))
+    }
+
+    #[test]
+    fn can_parse_patterns() {
+        assert_eq!(parse_pattern("_").unwrap(), Pattern::Wild);
+        assert_eq!(
+            parse_pattern("TaskState::Idle").unwrap(),
+            Pattern::Variant {
+                name: "Idle".to_string(),
+                fields: None
+            }
+        );
+        assert_eq!(
+            parse_pattern("Self::Loaded(ref count, ..)").unwrap(),
+            Pattern::Variant {
+                name: "Loaded".to_string(),
+                fields: Some(Fields::Tuple(vec![
+                    Some(Pattern::Binding("count".to_string())),
+                    None
+                ]))
+            }
+        );
+        assert_eq!(
+            parse_pattern("Editing { draft, index: 1, .. }").unwrap(),
+            Pattern::Variant {
+                name: "Editing".to_string(),
+                fields: Some(Fields::Struct(
+                    vec![
+                        ("draft".to_string(), Pattern::Binding("draft".to_string())),
+                        ("index".to_string(), Pattern::Lit(Value::Int(1))),
+                    ],
+                    true
+                ))
+            }
+        );
+        assert!(parse_pattern("Loaded(").is_err());
+        assert!(parse_pattern("Editing { draft").is_err());
+    }
+
+    #[test]
+    fn binds_fields_of_matching_variant() {
+        assert_eq!(
+            matches("Loaded(count, state)", &loaded()),
+            Ok(Some(vec![
+                ("count".to_string(), Some(Value::Int(1))),
+                ("state".to_string(), None)
+            ]))
+        );
+        assert_eq!(
+            matches("Loaded(.., state)", &loaded()),
+            Ok(Some(vec![("state".to_string(), None)]))
+        );
+        assert_eq!(matches("Loaded(2, _)", &loaded()), Ok(None));
+        assert_eq!(matches("Loading", &loaded()), Ok(None));
+    }
+
+    #[test]
+    fn unreflected_field_only_matches_bindings() {
+        assert_eq!(
+            matches("Loaded(_, State { tasks })", &loaded()),
+            Err(Error::Unreflected("Loaded(_, State { tasks })".to_string()))
+        );
+        assert!(matches("Loaded(count)", &loaded()).is_err());
+    }
+}
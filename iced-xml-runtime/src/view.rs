@@ -0,0 +1,520 @@
+use iced::{
+    Element, Length,
+    widget::{button, column, container, row, text},
+};
+use iced_xml_core::{
+    ButtonNode, Color, Component, ComponentNode, Font, HorizontalAlignment, MatchCaseNode,
+    MatchNode, Node, NodeLayout, TextNode, VerticalAlignment,
+};
+
+use crate::{
+    Error, Reflect, Value,
+    expr::{Bindings, ExprKind, eval, eval_message, parse_expr_or_lit, parse_message},
+    pattern::{Pattern, parse_pattern},
+};
+
+/// A node with its expressions evaluated against a model, ready to become widgets.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Interpreted<Message> {
+    Button {
+        content: String,
+        message: Option<Message>,
+    },
+    Text {
+        content: String,
+        size: f32,
+        color: Option<Color>,
+        font: Option<Font>,
+    },
+    Column(Vec<(Interpreted<Message>, NodeLayout)>),
+    Row(Vec<(Interpreted<Message>, NodeLayout)>),
+    /// The content of the matching case, `None` if it is empty
+    Match(Option<Box<(Interpreted<Message>, NodeLayout)>>),
+}
+
+/// Renders a parsed component against a model.
+pub fn view<'a, M>(component: &Component, model: &M) -> Result<Element<'a, M::Message>, Error>
+where
+    M: Reflect + ?Sized,
+    M::Message: Clone + 'a,
+{
+    match &component.content {
+        None => Ok(column![].into()),
+        Some(content) => Ok(view_interpreted(
+            interpret(content, model, &Vec::new())?,
+            content.layout,
+        )),
+    }
+}
+
+/// Checks that every binding of a component resolves against a model, without keeping the view.
+pub fn check<M>(component: &Component, model: &M) -> Result<(), Error>
+where
+    M: Reflect + ?Sized,
+{
+    match &component.content {
+        None => Ok(()),
+        Some(content) => interpret(content, model, &Vec::new()).map(|_| ()),
+    }
+}
+
+/// Evaluates the expressions of a node and its children.
+pub(crate) fn interpret<M: Reflect + ?Sized>(
+    node: &ComponentNode,
+    model: &M,
+    bindings: &Bindings,
+) -> Result<Interpreted<M::Message>, Error> {
+    let children = |content: &[Box<ComponentNode>]| {
+        content
+            .iter()
+            .map(|c| Ok((interpret(c, model, bindings)?, c.layout)))
+            .collect::<Result<Vec<_>, Error>>()
+    };
+    match &node.node {
+        Node::Button(button_node) => interpret_button_node(button_node, model, bindings),
+        Node::Text(text_node) => interpret_text_node(text_node, model, bindings),
+        Node::Column(column_node) => Ok(Interpreted::Column(children(&column_node.content)?)),
+        Node::Row(row_node) => Ok(Interpreted::Row(children(&row_node.content)?)),
+        Node::Match(match_node) => interpret_match_node(match_node, model, bindings),
+        Node::Placeholder(_) => Err(Error::UnsupportedNode("Placeholder")),
+    }
+}
+
+fn interpret_button_node<M: Reflect + ?Sized>(
+    node: &ButtonNode,
+    model: &M,
+    bindings: &Bindings,
+) -> Result<Interpreted<M::Message>, Error> {
+    let content = eval(&parse_expr_or_lit(&node.content)?, model, bindings)?;
+    let message = if node.message.is_empty() {
+        None
+    } else {
+        Some(eval_message(
+            &parse_message(&node.message)?,
+            model,
+            bindings,
+        )?)
+    };
+
+    Ok(Interpreted::Button {
+        content: content.to_string(),
+        message,
+    })
+}
+
+fn interpret_text_node<M: Reflect + ?Sized>(
+    node: &TextNode,
+    model: &M,
+    bindings: &Bindings,
+) -> Result<Interpreted<M::Message>, Error> {
+    let content = eval(&parse_expr_or_lit(&node.content)?, model, bindings)?;
+
+    Ok(Interpreted::Text {
+        content: content.to_string(),
+        size: node.size,
+        color: node.color,
+        font: node.font.clone(),
+    })
+}
+
+/// Interprets the first case whose pattern matches the value, with the names it binds.
+fn interpret_match_node<M: Reflect + ?Sized>(
+    node: &MatchNode,
+    model: &M,
+    bindings: &Bindings,
+) -> Result<Interpreted<M::Message>, Error> {
+    let value = parse_expr_or_lit(&node.value)?;
+    let on_self = value.kind == ExprKind::SelfRef;
+    let evaluated = eval(&value, model, bindings)?;
+    for case in &node.cases {
+        let (pattern, raw) = case_pattern(case, &evaluated, on_self)?;
+        let mut case_bindings = bindings.clone();
+        if pattern.matches(Some(&evaluated), &mut case_bindings, &raw)? {
+            let content = case
+                .content
+                .as_deref()
+                .map(|c| Ok::<_, Error>(Box::new((interpret(c, model, &case_bindings)?, c.layout))))
+                .transpose()?;
+            return Ok(Interpreted::Match(content));
+        }
+    }
+
+    Err(Error::NoMatchingCase(value.source))
+}
+
+/// The pattern of a case, along with its text for error messages.
+///
+/// Cases of a match on `self` name a variant of the model and destructure its fields like
+/// derived views do: `Case="Loaded"` binds `_0`, `_1`, .. or the names of the fields, and
+/// `Destructure="{State { tasks, .. }}"` is the pattern of its fields.
+fn case_pattern(
+    case: &MatchCaseNode,
+    value: &Value,
+    on_self: bool,
+) -> Result<(Pattern, String), Error> {
+    let destructure = case
+        .destructure
+        .as_deref()
+        .map(|d| trim_iced_expr(d.trim()));
+    let raw = match (on_self, value, destructure) {
+        (true, _, _) if case.case.trim() == "_" => "_".to_string(),
+        (true, Value::Variant(variant), destructure) => {
+            let tuple = variant.is_tuple();
+            let fields = match destructure {
+                Some(fields) => fields.to_string(),
+                None if tuple => (0..variant.fields.len())
+                    .map(|i| format!("_{i}"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                None => variant
+                    .fields
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+            match (fields.is_empty(), tuple) {
+                (true, _) => case.case.clone(),
+                (false, true) => format!("{}({fields})", case.case),
+                (false, false) => format!("{} {{ {fields} }}", case.case),
+            }
+        }
+        (_, _, Some(destructure)) => format!("{}{destructure}", case.case),
+        (_, _, None) => case.case.clone(),
+    };
+
+    Ok((parse_pattern(&raw)?, raw))
+}
+
+fn trim_iced_expr(expr: &str) -> &str {
+    expr.strip_prefix('{')
+        .and_then(|expr| expr.strip_suffix('}'))
+        .unwrap_or(expr)
+}
+
+fn view_interpreted<'a, Message: Clone + 'a>(
+    node: Interpreted<Message>,
+    layout: NodeLayout,
+) -> Element<'a, Message> {
+    let children = |content: Vec<(Interpreted<Message>, NodeLayout)>| {
+        content
+            .into_iter()
+            .map(|(child, layout)| view_interpreted(child, layout))
+            .collect::<Vec<_>>()
+    };
+    let content = match node {
+        Interpreted::Button { content, message } => {
+            button(text(content)).on_press_maybe(message).into()
+        }
+        Interpreted::Text {
+            content,
+            size,
+            color,
+            font,
+        } => {
+            let text = text(content)
+                .size(size)
+                .color_maybe(color.map(iced::Color::from));
+            match font {
+                Some(font) => text.font(&font).into(),
+                None => text.into(),
+            }
+        }
+        Interpreted::Column(content) => column(children(content)).into(),
+        Interpreted::Row(content) => row(children(content)).into(),
+        Interpreted::Match(Some(content)) => {
+            let (content, layout) = *content;
+            view_interpreted(content, layout)
+        }
+        Interpreted::Match(None) => column![].into(),
+    };
+
+    layout_container(layout, content)
+}
+
+/// Aligns, pads and sizes a node like the generated code does: aligned nodes fill the space
+/// given by their parent to be aligned within it, unless they are given a size.
+fn layout_container<'a, Message: 'a>(
+    layout: NodeLayout,
+    content: Element<'a, Message>,
+) -> Element<'a, Message> {
+    if layout == NodeLayout::default() {
+        return content;
+    }
+
+    let aligned = |length: Length| match length {
+        Length::Shrink => Length::Fill,
+        length => length,
+    };
+    let (width, height) = (Length::from(layout.width), Length::from(layout.height));
+    let content = container(content).padding(layout.padding.value);
+    let content = match layout.align_x {
+        HorizontalAlignment::Left => content.width(width),
+        HorizontalAlignment::Center => content.center_x(aligned(width)),
+        HorizontalAlignment::Right => content.align_right(aligned(width)),
+        HorizontalAlignment::Stretch => content.width(Length::Fill),
+    };
+    let content = match layout.align_y {
+        VerticalAlignment::Top => content.height(height),
+        VerticalAlignment::Center => content.center_y(aligned(height)),
+        VerticalAlignment::Bottom => content.align_bottom(aligned(height)),
+        VerticalAlignment::Stretch => content.height(Length::Fill),
+    };
+
+    content.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Value;
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Message {
+        Increment,
+        Add(i64),
+    }
+
+    struct Counter {
+        value: i64,
+    }
+
+    impl Reflect for Counter {
+        type Message = Message;
+
+        fn field(&self, name: &str) -> Option<Result<Value, Error>> {
+            match name {
+                "value" => Some(Ok(self.value.into())),
+                _ => None,
+            }
+        }
+
+        fn call(&self, name: &str, args: &[Value]) -> Option<Result<Value, Error>> {
+            match (name, args) {
+                ("doubled", []) => Some(Ok((self.value * 2).into())),
+                _ => None,
+            }
+        }
+
+        fn message(&self, name: &str, args: &[Value]) -> Option<Self::Message> {
+            match (name, args) {
+                ("Increment", []) => Some(Message::Increment),
+                ("Add", [value]) => value.as_int().map(Message::Add),
+                _ => None,
+            }
+        }
+    }
+
+    fn component(content: &str) -> Component {
+        iced_xml_parse::parse_str(&format!(
+            r#"<Component Message="Message">{content}</Component>"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn can_view_counter() {
+        let component = component(
+            r#"
+  <Column Padding="20">
+    <Button Message="Increment">Increment</Button>
+    <Text Size="50" Content="{self.value}" />
+    <Button Message="{Add(self.doubled())}">Double</Button>
+  </Column>"#,
+        );
+        let content = component.content.as_ref().unwrap();
+
+        assert_eq!(
+            interpret(content, &Counter { value: 1 }, &Vec::new()),
+            Ok(Interpreted::Column(vec![
+                (
+                    Interpreted::Button {
+                        content: "Increment".to_string(),
+                        message: Some(Message::Increment),
+                    },
+                    NodeLayout::default()
+                ),
+                (
+                    Interpreted::Text {
+                        content: "1".to_string(),
+                        size: 50.0,
+                        color: None,
+                        font: None,
+                    },
+                    NodeLayout::default()
+                ),
+                (
+                    Interpreted::Button {
+                        content: "Double".to_string(),
+                        message: Some(Message::Add(2)),
+                    },
+                    NodeLayout::default()
+                ),
+            ]))
+        );
+        assert_eq!(content.layout.padding.value, 20.0);
+        assert!(view(&component, &Counter { value: 1 }).is_ok());
+    }
+
+    #[test]
+    fn unknown_field_complains() {
+        let component = component(r#"<Text Content="{self.valeu}" />"#);
+
+        assert_eq!(
+            check(&component, &Counter { value: 1 }).unwrap_err(),
+            Error::UnknownField("valeu".to_string())
+        );
+    }
+
+    #[test]
+    fn unknown_message_complains() {
+        let component = component(r#"<Button Message="{Incremnt}">Increment</Button>"#);
+
+        assert_eq!(
+            check(&component, &Counter { value: 1 }).unwrap_err(),
+            Error::UnknownMessage("Incremnt".to_string())
+        );
+    }
+
+    #[test]
+    fn can_construct_message_with_args() {
+        let expr = parse_message("{Add(self.doubled())}").unwrap();
+
+        assert_eq!(
+            eval_message(&expr, &Counter { value: 21 }, &Vec::new()),
+            Ok(Message::Add(42))
+        );
+    }
+
+    /// A model like `enum Todos { Loading, Loaded(i64, State) }`, whose state is not reflected.
+    enum Todos {
+        Loading,
+        Loaded(i64),
+    }
+
+    impl Reflect for Todos {
+        type Message = Message;
+
+        fn field(&self, _name: &str) -> Option<Result<Value, Error>> {
+            None
+        }
+
+        fn message(&self, _name: &str, _args: &[Value]) -> Option<Self::Message> {
+            None
+        }
+
+        fn variant(&self) -> Option<crate::Variant> {
+            Some(match self {
+                Todos::Loading => crate::Variant::unit("Loading"),
+                Todos::Loaded(count) => crate::Variant {
+                    name: "Loaded".to_string(),
+                    fields: vec![
+                        ("0".to_string(), Some(Value::Int(*count))),
+                        ("1".to_string(), None),
+                    ],
+                },
+            })
+        }
+    }
+
+    fn text(content: &str) -> Interpreted<Message> {
+        Interpreted::Text {
+            content: content.to_string(),
+            size: 10.0,
+            color: None,
+            font: None,
+        }
+    }
+
+    #[test]
+    fn can_view_match_on_self() {
+        let component = component(
+            r#"
+  <Match Value="{self}">
+    <MatchCase Case="Loading">
+      <Text>Loading...</Text>
+    </MatchCase>
+    <MatchCase Case="Loaded" Destructure="{count, _}">
+      <Text Padding="5" Content="{count}" />
+    </MatchCase>
+  </Match>"#,
+        );
+        let content = component.content.as_ref().unwrap();
+        let case = |content: Interpreted<Message>, layout| {
+            Ok(Interpreted::Match(Some(Box::new((content, layout)))))
+        };
+
+        assert_eq!(
+            interpret(content, &Todos::Loading, &Vec::new()),
+            case(text("Loading..."), NodeLayout::default())
+        );
+        assert_eq!(
+            interpret(content, &Todos::Loaded(3), &Vec::new()),
+            case(
+                text("3"),
+                NodeLayout {
+                    padding: iced_xml_core::Padding { value: 5.0 },
+                    ..NodeLayout::default()
+                }
+            )
+        );
+        assert!(view(&component, &Todos::Loaded(3)).is_ok());
+    }
+
+    #[test]
+    fn match_without_destructure_binds_all_fields() {
+        let component = component(
+            r#"
+  <Match Value="{self}">
+    <MatchCase Case="Loaded"><Text Content="{_0}" /></MatchCase>
+    <MatchCase Case="_" />
+  </Match>"#,
+        );
+        let content = component.content.as_ref().unwrap();
+
+        assert_eq!(
+            interpret(content, &Todos::Loaded(3), &Vec::new()),
+            Ok(Interpreted::Match(Some(Box::new((
+                text("3"),
+                NodeLayout::default()
+            )))))
+        );
+        assert_eq!(
+            interpret(content, &Todos::Loading, &Vec::new()),
+            Ok(Interpreted::Match(None))
+        );
+    }
+
+    #[test]
+    fn match_complains_about_unmatched_or_unreflected_values() {
+        let unmatched = component(
+            r#"
+  <Match Value="{self}">
+    <MatchCase Case="Loading"><Text>Loading...</Text></MatchCase>
+  </Match>"#,
+        );
+        assert_eq!(
+            check(&unmatched, &Todos::Loaded(3)),
+            Err(Error::NoMatchingCase("self".to_string()))
+        );
+
+        let unreflected = component(
+            r#"
+  <Match Value="{self}">
+    <MatchCase Case="Loaded" Destructure="{_, state}"><Text Content="{state}" /></MatchCase>
+    <MatchCase Case="Loading"><Text>Loading...</Text></MatchCase>
+  </Match>"#,
+        );
+        assert_eq!(
+            check(&unreflected, &Todos::Loaded(3)),
+            Err(Error::Unreflected("state".to_string()))
+        );
+        assert!(check(&unreflected, &Todos::Loading).is_ok());
+
+        assert!(matches!(
+            check(&unreflected, &Counter { value: 1 }),
+            Err(Error::InvalidExpr { .. })
+        ));
+    }
+}